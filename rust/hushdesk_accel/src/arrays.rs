@@ -0,0 +1,39 @@
+use numpy::PyReadonlyArray1;
+use pyo3::prelude::*;
+use pyo3::types::{PyList, PyTuple};
+use std::borrow::Cow;
+
+/// A 1-D float input that borrows numpy memory when given an ndarray and
+/// falls back to copying plain lists/tuples, which never touch numpy.
+pub(crate) enum FloatArray<'py> {
+    Array(PyReadonlyArray1<'py, f64>),
+    Seq(Vec<f64>),
+}
+
+impl<'py> FromPyObject<'py> for FloatArray<'py> {
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if obj.is_instance_of::<PyList>() || obj.is_instance_of::<PyTuple>() {
+            return Ok(FloatArray::Seq(obj.extract()?));
+        }
+        Ok(FloatArray::Array(obj.extract()?))
+    }
+}
+
+impl FloatArray<'_> {
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            FloatArray::Array(array) => array.as_array().len(),
+            FloatArray::Seq(values) => values.len(),
+        }
+    }
+
+    pub(crate) fn values(&self) -> Cow<'_, [f64]> {
+        match self {
+            FloatArray::Array(array) => match array.as_slice() {
+                Ok(slice) => Cow::Borrowed(slice),
+                Err(_) => Cow::Owned(array.as_array().iter().copied().collect()),
+            },
+            FloatArray::Seq(values) => Cow::Borrowed(values),
+        }
+    }
+}
@@ -0,0 +1,1246 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+
+use crate::errors::{HushdeskBandWarning, HushdeskShapeError, HushdeskValueError};
+use crate::grid::{ordered_band, ordered_bands, warn_swapped, Axis};
+use crate::nan::NanPolicy;
+use crate::{consecutive_gaps, mean, median};
+
+const CENTER_MERGE_EPSILON: f64 = 2.0;
+const MIN_BAND_WIDTH: f64 = 5.0;
+// Histogram resolution used by `split_band`.
+const SPLIT_BAND_BINS: usize = 20;
+// Evidence weighting never moves the merge epsilon more than 2x either way.
+const MAX_EPSILON_SCALE: f64 = 2.0;
+// 64-bit FNV-1a parameters for `bands_fingerprint`.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Map per-day header centers to `{day: (x0, x1)}` column bands. The
+/// geometry runs with the GIL released; only the result dict needs it.
+/// With `warn=True`, bands dropped for being narrower than the minimum
+/// width, days merged away for `min_gap`, and multi-day groups inside the
+/// merge epsilon raise a `HushdeskBandWarning`. A `page_w` that is not positive and finite
+/// raises `HushdeskValueError` rather than returning no bands. Adjacent
+/// centers closer than `min_gap` are merged before any band is drawn; a
+/// negative or non-finite `min_gap` raises `HushdeskValueError`.
+/// With `gutters` (e.g. from `column_gaps`), the boundary between two
+/// centers sits at the middle of the gutter between them, else the midpoint.
+/// `left_margin`/`right_margin` bound the content area: centers outside it
+/// are dropped and the outermost bands run exactly to its edges.
+/// `with_rejected=True` returns `(bands, rejected)` where `rejected` lists
+/// the too-narrow bands as `(day, x0, x1, width)`, then each day the
+/// min-gap pass merged away as `(day, center, center, 0.0)`.
+/// Fewer than `min_columns` merged centers returns no bands, so a blank
+/// page does not read as one page-wide column.
+/// `strict=True` checks that the bands lie within `[0, page_w]`, do not
+/// overlap, and that no day was merged away for `min_gap`, raising
+/// `HushdeskValueError` if any of those failed.
+/// `edge_tol` lets edge bands overhang the page (or content) edges by that
+/// much for the minimum-width check; they are still reported clamped, and a
+/// band with no width left inside the page is rejected.
+/// `nan_policy` (`"skip"`, `"error"`, `"zero"`) governs non-finite centers.
+/// `key_by="xrank"` keys the bands by 0-based left-to-right column index
+/// instead of day, for pages whose header days are unreliable; `rejected`
+/// still names days. `trim_frac` (in `[0, 0.5)`) drops that fraction of
+/// each day's lowest and highest centers before averaging, so one misplaced
+/// token cannot drag its column; days too small to trim average as usual.
+///
+/// Per-day centers are ordered by `(center, day)` with a stable sort, so
+/// days sharing a center always take part in the geometry lowest day first.
+#[pyfunction]
+#[pyo3(signature = (
+    centers,
+    page_w,
+    warn = false,
+    min_gap = MIN_BAND_WIDTH,
+    gutters = None,
+    left_margin = None,
+    right_margin = None,
+    with_rejected = false,
+    min_columns = 0,
+    strict = false,
+    edge_tol = 0.0,
+    nan_policy = "skip",
+    key_by = "day",
+    trim_frac = 0.0,
+))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn select_bands(
+    py: Python<'_>,
+    centers: Vec<(i32, f64)>,
+    page_w: f64,
+    warn: bool,
+    min_gap: f64,
+    gutters: Option<Vec<(f64, f64)>>,
+    left_margin: Option<f64>,
+    right_margin: Option<f64>,
+    with_rejected: bool,
+    min_columns: usize,
+    strict: bool,
+    edge_tol: f64,
+    nan_policy: &str,
+    key_by: &str,
+    trim_frac: f64,
+) -> PyResult<PyObject> {
+    let selected = SelectBandsArgs {
+        page_w,
+        warn,
+        min_gap,
+        gutters,
+        left_margin,
+        right_margin,
+        with_rejected,
+        min_columns,
+        strict,
+        edge_tol,
+        nan_policy,
+        key_by,
+        trim_frac,
+    }
+    .run(py, centers)?;
+    let bands = bands_dict(py, selected.bands)?;
+    match selected.rejected {
+        Some(rejected) => Ok((bands, rejected).into_py(py)),
+        None => Ok(bands.into_py(py)),
+    }
+}
+
+/// `select_bands` as a list of `(day, (x0, x1))` in x order, with the same
+/// options. Every band is its own element, so nothing is lost if a key
+/// repeats and order never depends on dict iteration.
+#[pyfunction]
+#[pyo3(signature = (
+    centers,
+    page_w,
+    warn = false,
+    min_gap = MIN_BAND_WIDTH,
+    gutters = None,
+    left_margin = None,
+    right_margin = None,
+    with_rejected = false,
+    min_columns = 0,
+    strict = false,
+    edge_tol = 0.0,
+    nan_policy = "skip",
+    key_by = "day",
+    trim_frac = 0.0,
+))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn select_bands_list(
+    py: Python<'_>,
+    centers: Vec<(i32, f64)>,
+    page_w: f64,
+    warn: bool,
+    min_gap: f64,
+    gutters: Option<Vec<(f64, f64)>>,
+    left_margin: Option<f64>,
+    right_margin: Option<f64>,
+    with_rejected: bool,
+    min_columns: usize,
+    strict: bool,
+    edge_tol: f64,
+    nan_policy: &str,
+    key_by: &str,
+    trim_frac: f64,
+) -> PyResult<PyObject> {
+    let selected = SelectBandsArgs {
+        page_w,
+        warn,
+        min_gap,
+        gutters,
+        left_margin,
+        right_margin,
+        with_rejected,
+        min_columns,
+        strict,
+        edge_tol,
+        nan_policy,
+        key_by,
+        trim_frac,
+    }
+    .run(py, centers)?;
+    match selected.rejected {
+        Some(rejected) => Ok((selected.bands, rejected).into_py(py)),
+        None => Ok(selected.bands.into_py(py)),
+    }
+}
+
+/// Options of `select_bands` and `select_bands_list`, validated and run in
+/// one place so the two entry points cannot drift apart.
+struct SelectBandsArgs<'a> {
+    page_w: f64,
+    warn: bool,
+    min_gap: f64,
+    gutters: Option<Vec<(f64, f64)>>,
+    left_margin: Option<f64>,
+    right_margin: Option<f64>,
+    with_rejected: bool,
+    min_columns: usize,
+    strict: bool,
+    edge_tol: f64,
+    nan_policy: &'a str,
+    key_by: &'a str,
+    trim_frac: f64,
+}
+
+/// Keyed bands in x order, plus `(day, x0, x1, width)` rejects when asked.
+struct SelectedBands {
+    bands: Vec<(i32, (f64, f64))>,
+    rejected: Option<Vec<(i32, f64, f64, f64)>>,
+}
+
+impl SelectBandsArgs<'_> {
+    fn run(self, py: Python<'_>, centers: Vec<(i32, f64)>) -> PyResult<SelectedBands> {
+        let page_w = self.page_w;
+        check_page_width(page_w)?;
+        check_min_gap(self.min_gap)?;
+        let key = BandKey::parse(self.key_by)?;
+        if !(0.0..0.5).contains(&self.trim_frac) {
+            return Err(HushdeskValueError::new_err(format!(
+                "trim_frac must be in [0, 0.5), got {}",
+                self.trim_frac
+            )));
+        }
+        let centers = NanPolicy::parse(self.nan_policy)?.apply_keyed(&centers)?;
+        let options = BandOptions {
+            min_gap: self.min_gap,
+            gutters: self.gutters.unwrap_or_default(),
+            content: content_area(page_w, self.left_margin, self.right_margin)?,
+            min_columns: self.min_columns,
+            edge_tol: self.edge_tol.max(0.0),
+            trim_frac: self.trim_frac,
+            ..BandOptions::default()
+        };
+        let layout = py.allow_threads(|| layout_bands(&centers, None, page_w, &options));
+        if self.strict {
+            check_layout(&layout.bands, page_w)?;
+            check_absorbed(&layout.absorbed)?;
+        }
+        if self.warn {
+            warn_layout(py, &layout)?;
+        }
+        let rejected = self.with_rejected.then(|| {
+            layout
+                .rejected
+                .iter()
+                .map(|&(day, x0, x1)| (day, x0, x1, x1 - x0))
+                .chain(
+                    layout
+                        .absorbed
+                        .iter()
+                        .map(|&(day, _, center)| (day, center, center, 0.0)),
+                )
+                .collect()
+        });
+        Ok(SelectedBands {
+            bands: key.apply(layout.bands),
+            rejected,
+        })
+    }
+}
+
+/// Run `select_bands` over every page's centers with one shared `page_w`.
+/// Layouts are computed with the GIL released, in parallel unless
+/// `parallel=False`; results follow input order.
+#[pyfunction]
+#[pyo3(signature = (pages, page_w, parallel = true))]
+pub(crate) fn select_bands_batch(
+    py: Python<'_>,
+    pages: Vec<Vec<(i32, f64)>>,
+    page_w: f64,
+    parallel: bool,
+) -> PyResult<Vec<Py<PyDict>>> {
+    check_page_width(page_w)?;
+    let options = BandOptions::default();
+    let layouts: Vec<BandLayout> = py.allow_threads(|| {
+        if parallel {
+            pages
+                .par_iter()
+                .map(|centers| layout_bands(centers, None, page_w, &options))
+                .collect()
+        } else {
+            pages
+                .iter()
+                .map(|centers| layout_bands(centers, None, page_w, &options))
+                .collect()
+        }
+    });
+    layouts
+        .into_iter()
+        .map(|layout| bands_dict(py, layout.bands))
+        .collect()
+}
+
+/// Like `select_bands`, but average each day's centers weighted by
+/// `weights` (e.g. token bbox widths) so wide tokens pull the column more
+/// than stray digits. Entries with a non-positive weight are skipped.
+/// `evidence` scales the merge epsilon per neighbor pair by how much weight
+/// backs the weaker day relative to the page mean: `"uniform"` (fixed),
+/// `"linear"`, or `"sqrt"`. Low evidence widens the epsilon, high narrows it.
+/// `with_totals=True` returns `{day: ((x0, x1), total_weight)}`, the summed
+/// weight of the centers behind each band, so callers can drop weak columns.
+#[pyfunction]
+#[pyo3(signature = (centers, weights, page_w, evidence = "uniform", warn = false, with_totals = false))]
+pub(crate) fn select_bands_weighted(
+    py: Python<'_>,
+    centers: Vec<(i32, f64)>,
+    weights: Vec<f64>,
+    page_w: f64,
+    evidence: &str,
+    warn: bool,
+    with_totals: bool,
+) -> PyResult<PyObject> {
+    check_page_width(page_w)?;
+    check_weights(&centers, &weights)?;
+    let options = BandOptions {
+        evidence: EvidenceScheme::parse(evidence)?,
+        ..BandOptions::default()
+    };
+    let layout = py.allow_threads(|| layout_bands(&centers, Some(&weights), page_w, &options));
+    if warn {
+        warn_layout(py, &layout)?;
+    }
+    if !with_totals {
+        return Ok(bands_dict(py, layout.bands)?.into_py(py));
+    }
+    let dict = PyDict::new_bound(py);
+    for (day, band) in layout.bands {
+        let total = layout.evidence.get(&day).copied().unwrap_or(0.0);
+        dict.set_item(day, (band, total))?;
+    }
+    Ok(dict.into_py(py))
+}
+
+/// Audit `select_bands`: for every output band, count the input centers
+/// that fall inside it and break them down by their own day, as
+/// `{day: (count, {contributing_day: count})}`. A band fed by several days
+/// points at centers absorbed from the wrong column.
+#[pyfunction]
+pub(crate) fn band_assignments(
+    py: Python<'_>,
+    centers: Vec<(i32, f64)>,
+    page_w: f64,
+) -> PyResult<Py<PyDict>> {
+    check_page_width(page_w)?;
+    let tallies = py.allow_threads(|| {
+        let layout = layout_bands(&centers, None, page_w, &BandOptions::default());
+        let mut tallies: BTreeMap<i32, BTreeMap<i32, usize>> = layout
+            .bands
+            .iter()
+            .map(|(day, _)| (*day, BTreeMap::new()))
+            .collect();
+        let axis = Axis::new(layout.bands);
+        for &(day, center) in &centers {
+            // a center sitting exactly on a band's upper edge still counts for it
+            let hit = axis
+                .nearest(center)
+                .filter(|(_, distance)| *distance <= 0.0);
+            if let Some((index, _)) = hit {
+                let entry = tallies.entry(axis.key(index)).or_default();
+                *entry.entry(day).or_default() += 1;
+            }
+        }
+        tallies
+    });
+
+    let dict = PyDict::new_bound(py);
+    for (band_day, sources) in tallies {
+        let total: usize = sources.values().sum();
+        let breakdown = PyDict::new_bound(py);
+        for (day, count) in sources {
+            breakdown.set_item(day, count)?;
+        }
+        dict.set_item(band_day, (total, breakdown))?;
+    }
+    Ok(dict.unbind())
+}
+
+/// Only the averaging and grouping stages of `select_bands`: average each
+/// day's centers, group neighbors within `epsilon`, and collapse single-day
+/// groups. Returns the merged `(day, center)` list sorted by center.
+#[pyfunction]
+pub(crate) fn merge_centers(centers: Vec<(i32, f64)>, epsilon: f64) -> PyResult<Vec<(i32, f64)>> {
+    if !epsilon.is_finite() || epsilon < 0.0 {
+        return Err(HushdeskValueError::new_err(format!(
+            "epsilon must be finite and non-negative, got {epsilon}"
+        )));
+    }
+    let options = BandOptions {
+        merge_epsilon: epsilon,
+        ..BandOptions::default()
+    };
+    Ok(merge_day_centers(&centers, None, &options).centers)
+}
+
+/// Dominant spacing between adjacent column centers: the median of the
+/// non-zero gaps between sorted finite centers, or None without one.
+#[pyfunction]
+pub(crate) fn estimate_column_pitch(centers: Vec<f64>) -> PyResult<Option<f64>> {
+    let mut gaps: Vec<f64> = consecutive_gaps(&centers)
+        .into_iter()
+        .filter(|gap| *gap > 0.0)
+        .collect();
+    gaps.sort_by(|x, y| x.total_cmp(y));
+    Ok(median(&gaps))
+}
+
+/// Snap `centers` onto the regular lattice of spacing `pitch` that fits
+/// most of them. The lattice phase is the one putting the most centers
+/// within `tol` of a lattice point (least total offset on ties), refined to
+/// their mean offset; those centers move onto it, the rest stay put.
+/// Output follows input order.
+#[pyfunction]
+pub(crate) fn regularize_centers(centers: Vec<f64>, pitch: f64, tol: f64) -> PyResult<Vec<f64>> {
+    if !pitch.is_finite() || pitch <= 0.0 {
+        return Err(HushdeskValueError::new_err(format!(
+            "pitch must be positive and finite, got {pitch}"
+        )));
+    }
+    if !tol.is_finite() || tol < 0.0 {
+        return Err(HushdeskValueError::new_err(format!(
+            "tol must be finite and non-negative, got {tol}"
+        )));
+    }
+    // signed distance from `x` to the nearest lattice point at `phase`
+    let offset = |x: f64, phase: f64| {
+        let d = (x - phase).rem_euclid(pitch);
+        if d > pitch / 2.0 {
+            d - pitch
+        } else {
+            d
+        }
+    };
+    let finite: Vec<f64> = centers.iter().copied().filter(|x| x.is_finite()).collect();
+    let fit = |phase: f64| {
+        let inliers: Vec<f64> = finite
+            .iter()
+            .map(|&x| offset(x, phase))
+            .filter(|d| d.abs() <= tol)
+            .collect();
+        let error: f64 = inliers.iter().map(|d| d.abs()).sum();
+        (inliers, error)
+    };
+
+    let mut best: Option<(f64, usize, f64)> = None;
+    for &x in &finite {
+        let phase = x.rem_euclid(pitch);
+        let (inliers, error) = fit(phase);
+        let better = best.is_none_or(|(_, count, best_error)| {
+            inliers.len() > count || (inliers.len() == count && error < best_error)
+        });
+        if better {
+            let refined = phase + mean(&inliers);
+            best = Some((refined, inliers.len(), error));
+        }
+    }
+    let Some((phase, _, _)) = best else {
+        return Ok(centers);
+    };
+    Ok(centers
+        .into_iter()
+        .map(|x| {
+            if !x.is_finite() {
+                return x;
+            }
+            let d = offset(x, phase);
+            if d.abs() <= tol {
+                x - d
+            } else {
+                x
+            }
+        })
+        .collect())
+}
+
+/// Split an over-merged band at its deepest internal density valley. The
+/// xs inside `band` are binned into `SPLIT_BAND_BINS` equal bins; an interior
+/// bin's dip is `1 - count / min(tallest bin left, tallest bin right)`. When
+/// the best dip reaches `min_dip_ratio` the band splits at the middle of that
+/// valley (of a flat valley's run of bins), else it passes through whole.
+/// A `min_dip_ratio` outside `(0, 1]` raises `HushdeskValueError`.
+/// A reversed band is swapped with a `HushdeskBandWarning`.
+#[pyfunction]
+pub(crate) fn split_band(
+    py: Python<'_>,
+    xs_in_band: Vec<f64>,
+    band: (f64, f64),
+    min_dip_ratio: f64,
+) -> PyResult<Vec<(f64, f64)>> {
+    if !(min_dip_ratio > 0.0 && min_dip_ratio <= 1.0) {
+        return Err(HushdeskValueError::new_err(format!(
+            "min_dip_ratio must be in (0, 1], got {min_dip_ratio}"
+        )));
+    }
+    let (band, swapped) = ordered_band(band);
+    warn_swapped(py, "split_band", usize::from(swapped))?;
+    let (x0, x1) = band;
+    let width = x1 - x0;
+    if !width.is_finite() || width <= 0.0 {
+        return Ok(vec![band]);
+    }
+    let bin = width / SPLIT_BAND_BINS as f64;
+    let mut counts = [0usize; SPLIT_BAND_BINS];
+    for &x in &xs_in_band {
+        if x.is_finite() && x >= x0 && x <= x1 {
+            counts[(((x - x0) / bin) as usize).min(SPLIT_BAND_BINS - 1)] += 1;
+        }
+    }
+
+    let dip = |index: usize| {
+        let left = counts[..index].iter().copied().max().unwrap_or(0);
+        let right = counts[index + 1..].iter().copied().max().unwrap_or(0);
+        let shoulder = left.min(right);
+        if shoulder == 0 {
+            0.0
+        } else {
+            1.0 - counts[index] as f64 / shoulder as f64
+        }
+    };
+    let dips: Vec<f64> = (1..SPLIT_BAND_BINS - 1).map(dip).collect();
+    let best = dips.iter().copied().fold(0.0_f64, f64::max);
+    if best <= 0.0 || best < min_dip_ratio {
+        return Ok(vec![band]);
+    }
+    let start = dips.iter().position(|&d| d == best).unwrap_or(0);
+    let run = dips[start..].iter().take_while(|&&d| d == best).count();
+    // dips[i] belongs to bin i + 1
+    let split = x0 + (start + 1) as f64 * bin + run as f64 * bin / 2.0;
+    Ok(vec![(x0, split), (split, x1)])
+}
+
+/// For each detected band, the template day whose x-range it overlaps the
+/// most, or `None` when no overlap exceeds `tol` pixels. Matching uses the
+/// overlap length, not center distance; ties go to the earlier template entry.
+/// Reversed bands are swapped with a `HushdeskBandWarning`.
+#[pyfunction]
+pub(crate) fn match_to_template(
+    py: Python<'_>,
+    detected: Vec<(i32, (f64, f64))>,
+    template: Vec<(i32, (f64, f64))>,
+    tol: f64,
+) -> PyResult<Vec<(i32, Option<i32>)>> {
+    let detected = ordered_bands(py, "match_to_template", detected)?;
+    let template = ordered_bands(py, "match_to_template", template)?;
+    Ok(detected
+        .iter()
+        .map(|&(day, band)| {
+            let best = template
+                .iter()
+                .map(|&(template_day, span)| (template_day, overlap(band, span)))
+                .filter(|(_, shared)| *shared > tol)
+                .fold(None, |best: Option<(i32, f64)>, candidate| match best {
+                    Some(current) if current.1 >= candidate.1 => Some(current),
+                    _ => Some(candidate),
+                });
+            (day, best.map(|(template_day, _)| template_day))
+        })
+        .collect())
+}
+
+/// Length of the intersection of bands `a` and `b`; 0.0 when disjoint or
+/// merely touching. Reversed bands are swapped with a `HushdeskBandWarning`.
+#[pyfunction]
+pub(crate) fn band_overlap(py: Python<'_>, a: (f64, f64), b: (f64, f64)) -> PyResult<f64> {
+    let (a, b) = ordered_pair(py, "band_overlap", a, b)?;
+    Ok(overlap(a, b))
+}
+
+/// Intersection over union of bands `a` and `b`, in `[0, 1]`. Two
+/// zero-width bands have an IoU of 0.0. Reversed bands are swapped with a
+/// `HushdeskBandWarning`.
+#[pyfunction]
+pub(crate) fn band_iou(py: Python<'_>, a: (f64, f64), b: (f64, f64)) -> PyResult<f64> {
+    let (a, b) = ordered_pair(py, "band_iou", a, b)?;
+    let shared = overlap(a, b);
+    let union = (a.1 - a.0) + (b.1 - b.0) - shared;
+    Ok(if union > 0.0 { shared / union } else { 0.0 })
+}
+
+fn ordered_pair(
+    py: Python<'_>,
+    caller: &str,
+    a: (f64, f64),
+    b: (f64, f64),
+) -> PyResult<((f64, f64), (f64, f64))> {
+    let (a, a_swapped) = ordered_band(a);
+    let (b, b_swapped) = ordered_band(b);
+    warn_swapped(py, caller, usize::from(a_swapped) + usize::from(b_swapped))?;
+    Ok((a, b))
+}
+
+/// Stable 64-bit fingerprint of a band set for cache keys. Edges are
+/// ordered low to high, rounded to multiples of `quantum`, and the sorted
+/// `(day, x0, x1)` tuples are hashed with FNV-1a, so the value does not
+/// depend on input order, edge order, float formatting, or the toolchain.
+#[pyfunction]
+pub(crate) fn bands_fingerprint(bands: Vec<(i32, (f64, f64))>, quantum: f64) -> PyResult<u64> {
+    if !quantum.is_finite() || quantum <= 0.0 {
+        return Err(HushdeskValueError::new_err(format!(
+            "quantum must be positive and finite, got {quantum}"
+        )));
+    }
+    let mut rounded = Vec::with_capacity(bands.len());
+    for (day, (x0, x1)) in bands {
+        if !x0.is_finite() || !x1.is_finite() {
+            return Err(HushdeskValueError::new_err(format!(
+                "band for day {day} has non-finite edges ({x0}, {x1})"
+            )));
+        }
+        let ((x0, x1), _) = ordered_band((x0, x1));
+        let q0 = (x0 / quantum).round() as i64;
+        let q1 = (x1 / quantum).round() as i64;
+        rounded.push((i64::from(day), q0, q1));
+    }
+    rounded.sort_unstable();
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for (day, q0, q1) in rounded {
+        for value in [day, q0, q1] {
+            for byte in value.to_le_bytes() {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+        }
+    }
+    Ok(hash)
+}
+
+/// Length of the intersection of two intervals; 0.0 when they are disjoint
+/// or only touch. Reversed endpoints are read in order.
+pub(crate) fn overlap(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (a0, a1) = (a.0.min(a.1), a.0.max(a.1));
+    let (b0, b1) = (b.0.min(b.1), b.0.max(b.1));
+    (a1.min(b1) - a0.max(b0)).max(0.0)
+}
+
+/// Band unlabeled `centers` by known header `seeds` of `(day, x)`: each
+/// center joins its nearest seed, then bands come from the seeded groups.
+/// A center farther from its seed than half that seed's spacing to its
+/// closest neighbor seed (or `page_w` for a lone seed) is dropped.
+#[pyfunction]
+pub(crate) fn select_bands_seeded(
+    py: Python<'_>,
+    centers: Vec<f64>,
+    seeds: Vec<(i32, f64)>,
+    page_w: f64,
+) -> PyResult<Py<PyDict>> {
+    check_page_width(page_w)?;
+    let layout = py.allow_threads(|| {
+        let labeled = seed_centers(&centers, &seeds, page_w);
+        layout_bands(&labeled, None, page_w, &BandOptions::default())
+    });
+    bands_dict(py, layout.bands)
+}
+
+fn seed_centers(centers: &[f64], seeds: &[(i32, f64)], page_w: f64) -> Vec<(i32, f64)> {
+    let mut seeds: Vec<(i32, f64)> = seeds
+        .iter()
+        .copied()
+        .filter(|(_, x)| x.is_finite())
+        .collect();
+    seeds.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let reach: Vec<f64> = (0..seeds.len())
+        .map(|index| {
+            let prev = index.checked_sub(1).map(|i| seeds[index].1 - seeds[i].1);
+            let next = seeds.get(index + 1).map(|seed| seed.1 - seeds[index].1);
+            match (prev, next) {
+                (None, None) => page_w,
+                (a, b) => a.unwrap_or(f64::INFINITY).min(b.unwrap_or(f64::INFINITY)) / 2.0,
+            }
+        })
+        .collect();
+
+    centers
+        .iter()
+        .filter(|x| x.is_finite())
+        .filter_map(|&x| {
+            let index = seeds.partition_point(|seed| seed.1 < x);
+            let nearest = [index.checked_sub(1), Some(index)]
+                .into_iter()
+                .flatten()
+                .filter(|&i| i < seeds.len())
+                .min_by(|&a, &b| (x - seeds[a].1).abs().total_cmp(&(x - seeds[b].1).abs()))?;
+            ((x - seeds[nearest].1).abs() <= reach[nearest]).then_some((seeds[nearest].0, x))
+        })
+        .collect()
+}
+
+/// Assign each x to the day whose band contains it, snapping x within `tol`
+/// of a band edge to the nearest band. Returns -1 beyond tolerance.
+/// Reversed bands are swapped with a `HushdeskBandWarning`.
+#[pyfunction]
+pub(crate) fn assign_days(
+    py: Python<'_>,
+    xs: Vec<f64>,
+    bands: Vec<(i32, (f64, f64))>,
+    tol: f64,
+) -> PyResult<Vec<i32>> {
+    let axis = Axis::new(ordered_bands(py, "assign_days", bands)?);
+    Ok(py.allow_threads(|| {
+        xs.iter()
+            .map(|&x| match axis.nearest(x) {
+                Some((index, distance)) if distance <= tol => axis.key(index),
+                _ => -1,
+            })
+            .collect()
+    }))
+}
+
+/// Return every uncovered stretch of `[0, page_w]` wider than `max_gap`,
+/// including the leading and trailing margins. Empty means well covered.
+/// Reversed bands are swapped with a `HushdeskBandWarning`.
+#[pyfunction]
+pub(crate) fn validate_coverage(
+    py: Python<'_>,
+    bands: Vec<(i32, (f64, f64))>,
+    page_w: f64,
+    max_gap: f64,
+) -> PyResult<Vec<(f64, f64)>> {
+    let mut spans: Vec<(f64, f64)> = ordered_bands(py, "validate_coverage", bands)?
+        .into_iter()
+        .map(|(_, span)| span)
+        .filter(|(x0, x1)| x0.is_finite() && x1.is_finite())
+        .collect();
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut gaps = Vec::new();
+    let mut cursor = 0.0_f64;
+    for (x0, x1) in spans {
+        if x0 - cursor > max_gap {
+            gaps.push((cursor, x0));
+        }
+        cursor = cursor.max(x1);
+    }
+    if page_w - cursor > max_gap {
+        gaps.push((cursor, page_w));
+    }
+    Ok(gaps)
+}
+
+// Slack for float noise when comparing band edges.
+const LAYOUT_TOLERANCE: f64 = 1e-9;
+
+/// Verify bands stay inside `[0, page_w]` and are pairwise non-overlapping.
+fn check_layout(bands: &[(i32, (f64, f64))], page_w: f64) -> PyResult<()> {
+    let mut spans: Vec<(i32, f64, f64)> =
+        bands.iter().map(|&(day, (x0, x1))| (day, x0, x1)).collect();
+    spans.sort_by(|a, b| a.1.total_cmp(&b.1));
+    for &(day, x0, x1) in &spans {
+        if x0 < -LAYOUT_TOLERANCE || x1 > page_w + LAYOUT_TOLERANCE || x1 < x0 {
+            return Err(HushdeskValueError::new_err(format!(
+                "band for day {day} ({x0}, {x1}) falls outside [0, {page_w}]"
+            )));
+        }
+    }
+    for pair in spans.windows(2) {
+        let (left, right) = (pair[0], pair[1]);
+        if left.2 > right.1 + LAYOUT_TOLERANCE {
+            return Err(HushdeskValueError::new_err(format!(
+                "bands for days {} and {} overlap ({} > {})",
+                left.0, right.0, left.2, right.1
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn check_absorbed(absorbed: &[Absorbed]) -> PyResult<()> {
+    match absorbed.first() {
+        Some((day, into, center)) => Err(HushdeskValueError::new_err(format!(
+            "day {day} (center {center}) was merged into day {into}: centers closer than min_gap"
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn check_min_gap(min_gap: f64) -> PyResult<()> {
+    if !min_gap.is_finite() || min_gap < 0.0 {
+        return Err(HushdeskValueError::new_err(format!(
+            "min_gap must be finite and non-negative, got {min_gap}"
+        )));
+    }
+    Ok(())
+}
+
+fn check_page_width(page_w: f64) -> PyResult<()> {
+    check_page_extent("page_w", page_w)
+}
+
+/// Reject a page dimension that is not positive and finite; `name` is the
+/// argument it came from.
+pub(crate) fn check_page_extent(name: &str, extent: f64) -> PyResult<()> {
+    if !extent.is_finite() || extent <= 0.0 {
+        return Err(HushdeskValueError::new_err(format!(
+            "{name} must be positive and finite, got {extent}"
+        )));
+    }
+    Ok(())
+}
+
+fn content_area(
+    page_w: f64,
+    left_margin: Option<f64>,
+    right_margin: Option<f64>,
+) -> PyResult<Option<(f64, f64)>> {
+    if left_margin.is_none() && right_margin.is_none() {
+        return Ok(None);
+    }
+    let left = left_margin.unwrap_or(0.0);
+    let right = page_w - right_margin.unwrap_or(0.0);
+    if !(left.is_finite() && right.is_finite()) || left < 0.0 || right > page_w || left >= right {
+        return Err(HushdeskValueError::new_err(format!(
+            "margins leave no content area: left_margin={left_margin:?}, right_margin={right_margin:?}, page_w={page_w}"
+        )));
+    }
+    Ok(Some((left, right)))
+}
+
+fn check_weights(centers: &[(i32, f64)], weights: &[f64]) -> PyResult<()> {
+    if centers.len() != weights.len() {
+        return Err(HushdeskShapeError::new_err(format!(
+            "centers and weights differ in length ({} vs {})",
+            centers.len(),
+            weights.len()
+        )));
+    }
+    Ok(())
+}
+
+fn bands_dict(py: Python<'_>, bands: Vec<(i32, (f64, f64))>) -> PyResult<Py<PyDict>> {
+    let dict = PyDict::new_bound(py);
+    for (day, (x0, x1)) in bands {
+        dict.set_item(day, (x0, x1))?;
+    }
+    Ok(dict.unbind())
+}
+
+/// What the keys of a `select_bands` result identify.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum BandKey {
+    /// The day label each band was built from.
+    #[default]
+    Day,
+    /// The band's 0-based position in left-to-right order.
+    XRank,
+}
+
+impl BandKey {
+    pub(crate) fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "day" => Ok(BandKey::Day),
+            "xrank" => Ok(BandKey::XRank),
+            other => Err(HushdeskValueError::new_err(format!(
+                "unknown key_by {other:?}; expected \"day\" or \"xrank\""
+            ))),
+        }
+    }
+
+    /// Re-key `bands`, which `layout_bands` emits in x order.
+    fn apply(self, bands: Vec<(i32, (f64, f64))>) -> Vec<(i32, (f64, f64))> {
+        match self {
+            BandKey::Day => bands,
+            BandKey::XRank => bands
+                .into_iter()
+                .enumerate()
+                .map(|(rank, (_, band))| (rank as i32, band))
+                .collect(),
+        }
+    }
+}
+
+/// How per-day evidence bends the center merge epsilon.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum EvidenceScheme {
+    /// Every pair uses the base merge epsilon.
+    #[default]
+    Uniform,
+    /// Epsilon divided by the weaker day's evidence ratio.
+    Linear,
+    /// Epsilon divided by the square root of that ratio.
+    Sqrt,
+}
+
+impl EvidenceScheme {
+    pub(crate) fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "uniform" => Ok(EvidenceScheme::Uniform),
+            "linear" => Ok(EvidenceScheme::Linear),
+            "sqrt" => Ok(EvidenceScheme::Sqrt),
+            other => Err(HushdeskValueError::new_err(format!(
+                "unknown evidence scheme {other:?}; expected \"uniform\", \"linear\", or \"sqrt\""
+            ))),
+        }
+    }
+
+    /// Merge epsilon for a pair whose weaker side carries `ratio` times the
+    /// mean evidence, bounded to half/double the `base` epsilon.
+    fn epsilon(self, base: f64, ratio: f64) -> f64 {
+        let factor = match self {
+            EvidenceScheme::Uniform => 1.0,
+            EvidenceScheme::Linear => ratio,
+            EvidenceScheme::Sqrt => ratio.sqrt(),
+        };
+        if !factor.is_finite() || factor <= 0.0 {
+            return base * MAX_EPSILON_SCALE;
+        }
+        base * (1.0 / factor).clamp(1.0 / MAX_EPSILON_SCALE, MAX_EPSILON_SCALE)
+    }
+}
+
+/// Tuning knobs for `layout_bands`; the default reproduces `select_bands`.
+#[derive(Clone, Debug)]
+pub(crate) struct BandOptions {
+    /// Base distance within which averaged day centers group together.
+    pub(crate) merge_epsilon: f64,
+    pub(crate) evidence: EvidenceScheme,
+    /// Adjacent centers closer than this merge before the geometry step.
+    pub(crate) min_gap: f64,
+    /// Whitespace intervals; a gutter between two centers sets their boundary.
+    pub(crate) gutters: Vec<(f64, f64)>,
+    /// `(left, right)` edges of the area between the page margins.
+    pub(crate) content: Option<(f64, f64)>,
+    /// Fewer merged centers than this yields an empty layout.
+    pub(crate) min_columns: usize,
+    /// Slack past `0`/`page_w` allowed before clamping edge bands.
+    pub(crate) edge_tol: f64,
+    /// Share of each day's centers trimmed from both ends before averaging.
+    pub(crate) trim_frac: f64,
+}
+
+impl Default for BandOptions {
+    fn default() -> Self {
+        BandOptions {
+            merge_epsilon: CENTER_MERGE_EPSILON,
+            evidence: EvidenceScheme::default(),
+            min_gap: MIN_BAND_WIDTH,
+            gutters: Vec::new(),
+            content: None,
+            min_columns: 0,
+            edge_tol: 0.0,
+            trim_frac: 0.0,
+        }
+    }
+}
+
+/// A day folded into a neighbor by the min-gap pass, as
+/// `(day, absorbing day, center)`.
+type Absorbed = (i32, i32, f64);
+
+/// Accepted bands plus what the geometry pass discarded or left suspicious.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct BandLayout {
+    pub(crate) bands: Vec<(i32, (f64, f64))>,
+    /// Bands dropped by the minimum-width check, as `(day, x0, x1)` with the
+    /// edges that check saw.
+    pub(crate) rejected: Vec<(i32, f64, f64)>,
+    /// Merged center behind each entry of `bands`, in the same order.
+    pub(crate) centers: Vec<(i32, f64)>,
+    /// Days folded into a neighbor by the min-gap pass.
+    pub(crate) absorbed: Vec<Absorbed>,
+    /// Days whose averaged centers fell within the merge epsilon of each other,
+    /// with the widest epsilon that joined them.
+    pub(crate) crowded: Vec<(Vec<i32>, f64)>,
+    /// Summed weight behind each merged center, including min-gap merges.
+    pub(crate) evidence: BTreeMap<i32, f64>,
+}
+
+fn warn_layout(py: Python<'_>, layout: &BandLayout) -> PyResult<()> {
+    let category = py.get_type_bound::<HushdeskBandWarning>();
+    for (day, x0, x1) in &layout.rejected {
+        let message = format!(
+            "select_bands dropped day {day}: band ({x0:.1}, {x1:.1}) is narrower than {MIN_BAND_WIDTH}"
+        );
+        PyErr::warn_bound(py, &category, &message, 1)?;
+    }
+    for (day, into, center) in &layout.absorbed {
+        let message = format!(
+            "select_bands dropped day {day}: center {center:.1} is closer than min_gap to its neighbor, merged into day {into}"
+        );
+        PyErr::warn_bound(py, &category, &message, 1)?;
+    }
+    for (days, epsilon) in &layout.crowded {
+        let merged = days
+            .iter()
+            .any(|day| layout.absorbed.iter().any(|(gone, _, _)| gone == day));
+        let verb = if merged { "merged" } else { "kept" };
+        let message = format!(
+            "select_bands {verb} days {days:?} whose centers lie within {epsilon} of each other"
+        );
+        PyErr::warn_bound(py, &category, &message, 1)?;
+    }
+    Ok(())
+}
+
+/// Run the per-day averaging, epsilon grouping, and band geometry. With
+/// `weights` (aligned with `centers`), per-day averages are weighted means.
+/// Averaged days are stably sorted by `(center, day)` before grouping.
+pub(crate) fn layout_bands(
+    centers: &[(i32, f64)],
+    weights: Option<&[f64]>,
+    page_w: f64,
+    options: &BandOptions,
+) -> BandLayout {
+    let mut layout = BandLayout::default();
+    if centers.is_empty() {
+        return layout;
+    }
+
+    let grouped = merge_day_centers(centers, weights, options);
+    layout.crowded = grouped.crowded;
+    let (merged, absorbed) = enforce_min_gap(&grouped.centers, &grouped.evidence, options.min_gap);
+    layout.absorbed = absorbed;
+    if merged.len() < options.min_columns {
+        return layout;
+    }
+
+    let count = merged.len();
+    layout.evidence = merged
+        .iter()
+        .map(|&(day, _, weight)| (day, weight))
+        .collect();
+    let merged: Vec<(i32, f64)> = merged
+        .into_iter()
+        .map(|(day, center, _)| (day, center))
+        .collect();
+    for (index, (day, center_x)) in merged.iter().enumerate() {
+        let mut x0;
+        let mut x1;
+        if count == 1 {
+            x0 = 0.0;
+            x1 = page_w;
+        } else if index == 0 {
+            let next_center = merged.get(index + 1).map(|(_, c)| *c).unwrap_or(*center_x);
+            let delta = (next_center - *center_x) / 2.0;
+            x0 = *center_x - delta;
+            x1 = *center_x + delta;
+        } else if index == count - 1 {
+            let prev_center = merged.get(index - 1).map(|(_, c)| *c).unwrap_or(*center_x);
+            let delta = (*center_x - prev_center) / 2.0;
+            x0 = *center_x - delta;
+            x1 = *center_x + delta;
+        } else {
+            let prev_center = merged.get(index - 1).map(|(_, c)| *c).unwrap_or(*center_x);
+            let next_center = merged.get(index + 1).map(|(_, c)| *c).unwrap_or(*center_x);
+            x0 = *center_x - (*center_x - prev_center) / 2.0;
+            x1 = *center_x + (next_center - *center_x) / 2.0;
+        }
+        if count > 1 {
+            let left = index
+                .checked_sub(1)
+                .and_then(|prev| gutter_between(&options.gutters, merged[prev].1, *center_x));
+            let right = merged
+                .get(index + 1)
+                .and_then(|next| gutter_between(&options.gutters, *center_x, next.1));
+            if let Some(boundary) = left {
+                x0 = boundary;
+                if index == count - 1 {
+                    x1 = 2.0 * *center_x - boundary;
+                }
+            }
+            if let Some(boundary) = right {
+                x1 = boundary;
+                if index == 0 {
+                    x0 = 2.0 * *center_x - boundary;
+                }
+            }
+        }
+
+        let (lo, hi) = options.content.unwrap_or((0.0, page_w));
+        if options.content.is_some() {
+            if index == 0 {
+                x0 = lo;
+            }
+            if index == count - 1 {
+                x1 = hi;
+            }
+        }
+        x0 = x0.max(lo - options.edge_tol);
+        x1 = x1.min(hi + options.edge_tol);
+        if x1 < x0 {
+            std::mem::swap(&mut x0, &mut x1);
+        }
+
+        // the width check sees the tolerant edges; callers see the page edges,
+        // and a band the final clamp collapses is dropped however wide it was
+        let tolerant = (x0, x1);
+        if options.edge_tol > 0.0 {
+            x0 = x0.max(lo);
+            x1 = x1.min(hi);
+        }
+        if tolerant.1 - tolerant.0 < MIN_BAND_WIDTH || x1 <= x0 {
+            layout.rejected.push((*day, tolerant.0, tolerant.1));
+            continue;
+        }
+        layout.bands.push((*day, (x0, x1)));
+        layout.centers.push((*day, *center_x));
+    }
+
+    layout
+}
+
+/// Per-day averages after epsilon grouping, with the evidence behind each
+/// day and the multi-day groups that fell within the epsilon.
+pub(crate) struct MergedCenters {
+    pub(crate) centers: Vec<(i32, f64)>,
+    pub(crate) evidence: BTreeMap<i32, f64>,
+    pub(crate) crowded: Vec<(Vec<i32>, f64)>,
+}
+
+/// The averaging and collapse stages of `layout_bands`, without geometry.
+pub(crate) fn merge_day_centers(
+    centers: &[(i32, f64)],
+    weights: Option<&[f64]>,
+    options: &BandOptions,
+) -> MergedCenters {
+    let mut crowded: Vec<(Vec<i32>, f64)> = Vec::new();
+    let mut per_day: BTreeMap<i32, Vec<(f64, f64)>> = BTreeMap::new();
+    for (index, &(day, center)) in centers.iter().enumerate() {
+        let weight = weights.map_or(1.0, |w| w[index]);
+        if !center.is_finite() || !weight.is_finite() || weight <= 0.0 {
+            continue;
+        }
+        if let Some((left, right)) = options.content {
+            if center < left || center > right {
+                continue;
+            }
+        }
+        per_day.entry(day).or_default().push((center, weight));
+    }
+
+    let mut evidence: BTreeMap<i32, f64> = BTreeMap::new();
+    let mut averaged: Vec<(i32, f64)> = per_day
+        .into_iter()
+        .filter_map(|(day, mut values)| {
+            let trim = (values.len() as f64 * options.trim_frac).floor() as usize;
+            if trim > 0 && values.len() > 2 * trim {
+                values.sort_by(|a, b| a.0.total_cmp(&b.0));
+                values.truncate(values.len() - trim);
+                values.drain(..trim);
+            }
+            if values.is_empty() {
+                None
+            } else {
+                let total: f64 = values.iter().map(|(_, w)| *w).sum();
+                let sum: f64 = values.iter().map(|(c, w)| c * w).sum();
+                evidence.insert(day, total);
+                Some((day, sum / total))
+            }
+        })
+        .collect();
+    let mean_evidence = evidence.values().sum::<f64>() / evidence.len().max(1) as f64;
+    let pair_epsilon = |a: i32, b: i32| {
+        let weaker = evidence[&a].min(evidence[&b]);
+        options
+            .evidence
+            .epsilon(options.merge_epsilon, weaker / mean_evidence)
+    };
+
+    // stable total order on (center, day) so identical centers resolve the
+    // same way regardless of input order, seeds, or weights
+    averaged.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+
+    let mut merged: Vec<(i32, f64)> = Vec::new();
+    if let Some(first) = averaged.first().cloned() {
+        let mut group: Vec<(i32, f64)> = vec![first];
+        // widest epsilon that joined a pair in the current group
+        let mut group_epsilon = 0.0_f64;
+        for entry in averaged.into_iter().skip(1) {
+            if let Some(&(last_day, last_center)) = group.last() {
+                let epsilon = pair_epsilon(last_day, entry.0);
+                if (entry.1 - last_center).abs() <= epsilon {
+                    group.push(entry);
+                    group_epsilon = group_epsilon.max(epsilon);
+                } else {
+                    note_crowded(&mut crowded, &group, group_epsilon);
+                    merged.extend(collapse_center_group(&group));
+                    group = vec![entry];
+                    group_epsilon = 0.0;
+                }
+            }
+        }
+        note_crowded(&mut crowded, &group, group_epsilon);
+        merged.extend(collapse_center_group(&group));
+    }
+    MergedCenters {
+        centers: merged,
+        evidence,
+        crowded,
+    }
+}
+
+/// Midpoint of the widest gutter whose own midpoint lies strictly between
+/// the centers `a` and `b`.
+fn gutter_between(gutters: &[(f64, f64)], a: f64, b: f64) -> Option<f64> {
+    gutters
+        .iter()
+        .filter(|(g0, g1)| g0.is_finite() && g1.is_finite())
+        .map(|&(g0, g1)| ((g0 + g1) / 2.0, (g1 - g0).abs()))
+        .filter(|&(mid, _)| a < mid && mid < b)
+        .max_by(|x, y| x.1.total_cmp(&y.1))
+        .map(|(mid, _)| mid)
+}
+
+/// Fold each center closer than `min_gap` to its left neighbor into it, so
+/// the geometry never sees a pair that can only yield degenerate bands. The
+/// merged center is the evidence-weighted mean and keeps the better-backed
+/// day (the right one on ties, which is the day the width check would have
+/// kept). Returns `(day, center, evidence)` plus each folded-away day as
+/// `(day, absorbing day, center)`.
+fn enforce_min_gap(
+    centers: &[(i32, f64)],
+    evidence: &BTreeMap<i32, f64>,
+    min_gap: f64,
+) -> (Vec<(i32, f64, f64)>, Vec<Absorbed>) {
+    let mut kept: Vec<(i32, f64, f64)> = Vec::with_capacity(centers.len());
+    let mut folded: Vec<(i32, usize, f64)> = Vec::new();
+    for &(day, center) in centers {
+        let weight = evidence.get(&day).copied().unwrap_or(1.0);
+        let group = kept.len().wrapping_sub(1);
+        match kept.last_mut() {
+            Some(last) if center - last.1 < min_gap => {
+                let total = last.2 + weight;
+                if weight >= last.2 {
+                    folded.push((last.0, group, last.1));
+                    last.0 = day;
+                } else {
+                    folded.push((day, group, center));
+                }
+                last.1 = (last.1 * last.2 + center * weight) / total;
+                last.2 = total;
+            }
+            _ => kept.push((day, center, weight)),
+        }
+    }
+    let absorbed = folded
+        .into_iter()
+        .map(|(day, group, center)| (day, kept[group].0, center))
+        .collect();
+    (kept, absorbed)
+}
+
+fn note_crowded(crowded: &mut Vec<(Vec<i32>, f64)>, group: &[(i32, f64)], epsilon: f64) {
+    let first_day = group.first().map(|(day, _)| *day);
+    if group.iter().any(|(day, _)| Some(*day) != first_day) {
+        crowded.push((group.iter().map(|(day, _)| *day).collect(), epsilon));
+    }
+}
+
+fn collapse_center_group(group: &[(i32, f64)]) -> Vec<(i32, f64)> {
+    if group.is_empty() {
+        return Vec::new();
+    }
+    if group.len() == 1 {
+        return group.to_vec();
+    }
+    let first_day = group[0].0;
+    let single_day = group.iter().all(|(day, _)| *day == first_day);
+    if single_day {
+        let avg = group.iter().map(|(_, value)| *value).sum::<f64>() / group.len() as f64;
+        vec![(first_day, avg)]
+    } else {
+        group.to_vec()
+    }
+}
@@ -0,0 +1,40 @@
+use pyo3::create_exception;
+use pyo3::exceptions::{PyUserWarning, PyValueError};
+use pyo3::prelude::*;
+
+// Both derive from ValueError so existing `except ValueError` callers keep working.
+create_exception!(
+    hushdesk_accel,
+    HushdeskValueError,
+    PyValueError,
+    "An argument is outside the accepted domain."
+);
+create_exception!(
+    hushdesk_accel,
+    HushdeskShapeError,
+    HushdeskValueError,
+    "Parallel inputs disagree in length or shape."
+);
+create_exception!(
+    hushdesk_accel,
+    HushdeskBandWarning,
+    PyUserWarning,
+    "A band was dropped, kept despite crowding, or given with reversed edges."
+);
+
+pub(crate) fn register(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = module.py();
+    module.add(
+        "HushdeskValueError",
+        py.get_type_bound::<HushdeskValueError>(),
+    )?;
+    module.add(
+        "HushdeskShapeError",
+        py.get_type_bound::<HushdeskShapeError>(),
+    )?;
+    module.add(
+        "HushdeskBandWarning",
+        py.get_type_bound::<HushdeskBandWarning>(),
+    )?;
+    Ok(())
+}
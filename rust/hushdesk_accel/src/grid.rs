@@ -0,0 +1,289 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::BTreeMap;
+
+use crate::errors::{HushdeskBandWarning, HushdeskShapeError};
+
+/// Key reported by `assign_to_band` for values outside every band.
+const NO_BAND: i32 = -1;
+
+/// Bucket key for tokens that fall outside every cell.
+const OUTSIDE: (i32, i32) = (-1, -1);
+
+/// One axis of a grid: bands sorted by their lower edge so lookups are a
+/// binary search. Bands are half-open, `[lo, hi)`; reversed edges are
+/// swapped on construction. Bands may overlap or nest: a value inside
+/// several belongs to the one starting last.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Axis {
+    keys: Vec<i32>,
+    lo: Vec<f64>,
+    hi: Vec<f64>,
+    /// Running max of `hi`, so a lookup stops scanning left as soon as no
+    /// earlier band can reach the value.
+    reach: Vec<f64>,
+}
+
+impl Axis {
+    pub(crate) fn new(bands: Vec<(i32, (f64, f64))>) -> Self {
+        let mut bands: Vec<(i32, (f64, f64))> = bands
+            .into_iter()
+            .map(|(key, band)| (key, ordered_band(band).0))
+            .filter(|(_, (lo, hi))| lo.is_finite() && hi.is_finite() && hi > lo)
+            .collect();
+        bands.sort_by(|a, b| a.1 .0.total_cmp(&b.1 .0).then(a.0.cmp(&b.0)));
+
+        let mut axis = Axis::default();
+        for (key, (lo, hi)) in bands {
+            let reach = axis.reach.last().map_or(hi, |last| last.max(hi));
+            axis.keys.push(key);
+            axis.lo.push(lo);
+            axis.hi.push(hi);
+            axis.reach.push(reach);
+        }
+        axis
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Indices of the bands containing `value`, latest-starting first.
+    fn containing(&self, value: f64, closed: bool) -> impl Iterator<Item = usize> + '_ {
+        let end = if value.is_finite() {
+            self.lo.partition_point(|lo| *lo <= value)
+        } else {
+            0
+        };
+        let inside = move |edge: f64| if closed { value <= edge } else { value < edge };
+        (0..end)
+            .rev()
+            .take_while(move |&index| inside(self.reach[index]))
+            .filter(move |&index| inside(self.hi[index]))
+    }
+
+    /// Index of the band containing `value`, if any.
+    pub(crate) fn locate(&self, value: f64) -> Option<usize> {
+        self.containing(value, false).next()
+    }
+
+    /// Like `locate`, but with `closed` the upper edge is inclusive too; a
+    /// value on an edge shared by two bands goes to the lower band.
+    pub(crate) fn locate_with(&self, value: f64, closed: bool) -> Option<usize> {
+        if !closed {
+            return self.locate(value);
+        }
+        self.containing(value, true)
+            .find(|&index| self.hi[index] == value)
+            .or_else(|| self.locate(value))
+    }
+
+    /// Index of the band whose nearer edge is closest to `value`, with that
+    /// distance; a containing band is at distance zero. Ties go left.
+    pub(crate) fn nearest(&self, value: f64) -> Option<(usize, f64)> {
+        if !value.is_finite() {
+            return None;
+        }
+        if let Some(index) = self.locate(value) {
+            return Some((index, 0.0));
+        }
+        (0..self.len())
+            .map(|index| {
+                let distance = if value < self.lo[index] {
+                    self.lo[index] - value
+                } else {
+                    value - self.hi[index]
+                };
+                (index, distance)
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    pub(crate) fn key(&self, index: usize) -> i32 {
+        self.keys[index]
+    }
+
+    fn span(&self, key: i32) -> Option<(f64, f64)> {
+        let index = self.keys.iter().position(|k| *k == key)?;
+        Some((self.lo[index], self.hi[index]))
+    }
+}
+
+/// Row and column bands with fast point-to-cell lookup. Cells are keyed by
+/// `(row, col)` band keys (e.g. the day for columns).
+#[pyclass]
+pub(crate) struct Grid {
+    rows: Axis,
+    cols: Axis,
+}
+
+impl Grid {
+    pub(crate) fn from_bands(rows: Vec<(i32, (f64, f64))>, cols: Vec<(i32, (f64, f64))>) -> Self {
+        Grid {
+            rows: Axis::new(rows),
+            cols: Axis::new(cols),
+        }
+    }
+
+    pub(crate) fn cell(&self, x: f64, y: f64) -> Option<(i32, i32)> {
+        let row = self.rows.locate(y)?;
+        let col = self.cols.locate(x)?;
+        Some((self.rows.keys[row], self.cols.keys[col]))
+    }
+}
+
+#[pymethods]
+impl Grid {
+    /// Reversed row or column bands are swapped with a `HushdeskBandWarning`.
+    #[new]
+    fn py_new(
+        py: Python<'_>,
+        rows: Vec<(i32, (f64, f64))>,
+        cols: Vec<(i32, (f64, f64))>,
+    ) -> PyResult<Self> {
+        let rows = ordered_bands(py, "Grid", rows)?;
+        let cols = ordered_bands(py, "Grid", cols)?;
+        Ok(Grid::from_bands(rows, cols))
+    }
+
+    /// Return the `(row, col)` keys of the cell containing `(x, y)`.
+    fn cell_of(&self, x: f64, y: f64) -> Option<(i32, i32)> {
+        self.cell(x, y)
+    }
+
+    /// Return the `(x0, y0, x1, y1)` rectangle of a cell.
+    fn rect(&self, row: i32, col: i32) -> Option<(f64, f64, f64, f64)> {
+        let (y0, y1) = self.rows.span(row)?;
+        let (x0, x1) = self.cols.span(col)?;
+        Some((x0, y0, x1, y1))
+    }
+
+    /// Classify every `(x, y)` point, returning `(-1, -1)` for points outside
+    /// the grid. Half-open cells mean a point on a shared edge lands in
+    /// exactly one cell.
+    fn assign(&self, py: Python<'_>, points: Vec<(f64, f64)>) -> Vec<(i32, i32)> {
+        py.allow_threads(|| {
+            points
+                .iter()
+                .map(|&(x, y)| self.cell(x, y).unwrap_or(OUTSIDE))
+                .collect()
+        })
+    }
+
+    /// Return `(row_count, col_count)`.
+    fn shape(&self) -> (usize, usize) {
+        (self.rows.len(), self.cols.len())
+    }
+}
+
+/// Group token texts by the grid cell containing `(xs[i], ys[i])`, keeping
+/// input order within each cell. Tokens outside the grid are collected
+/// under the `(-1, -1)` key.
+#[pyfunction]
+pub(crate) fn bucket_tokens(
+    py: Python<'_>,
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    texts: Vec<String>,
+    grid: PyRef<'_, Grid>,
+) -> PyResult<Py<PyDict>> {
+    if xs.len() != ys.len() || xs.len() != texts.len() {
+        return Err(HushdeskShapeError::new_err(format!(
+            "xs, ys, and texts differ in length ({}, {}, {})",
+            xs.len(),
+            ys.len(),
+            texts.len()
+        )));
+    }
+
+    let mut buckets: BTreeMap<(i32, i32), Vec<String>> = BTreeMap::new();
+    for ((x, y), text) in xs.into_iter().zip(ys).zip(texts) {
+        let key = grid.cell(x, y).unwrap_or(OUTSIDE);
+        buckets.entry(key).or_default().push(text);
+    }
+
+    let dict = PyDict::new_bound(py);
+    for (key, texts) in buckets {
+        dict.set_item(key, texts)?;
+    }
+    Ok(dict.unbind())
+}
+
+/// `band` with its edges in increasing order, and whether they were swapped.
+pub(crate) fn ordered_band((x0, x1): (f64, f64)) -> ((f64, f64), bool) {
+    if x1 < x0 {
+        ((x1, x0), true)
+    } else {
+        ((x0, x1), false)
+    }
+}
+
+/// Order the edges of every band, raising one `HushdeskBandWarning` naming
+/// `caller` when any arrived as `(x1, x0)`.
+pub(crate) fn ordered_bands(
+    py: Python<'_>,
+    caller: &str,
+    bands: Vec<(i32, (f64, f64))>,
+) -> PyResult<Vec<(i32, (f64, f64))>> {
+    let mut swapped = 0;
+    let bands = bands
+        .into_iter()
+        .map(|(key, band)| {
+            let (band, was_swapped) = ordered_band(band);
+            swapped += usize::from(was_swapped);
+            (key, band)
+        })
+        .collect();
+    warn_swapped(py, caller, swapped)?;
+    Ok(bands)
+}
+
+/// Raise a `HushdeskBandWarning` from `caller` when it swapped any bands.
+pub(crate) fn warn_swapped(py: Python<'_>, caller: &str, swapped: usize) -> PyResult<()> {
+    if swapped > 0 {
+        let category = py.get_type_bound::<HushdeskBandWarning>();
+        let message = format!("{caller} swapped {swapped} band(s) given with x0 > x1");
+        PyErr::warn_bound(py, &category, &message, 1)?;
+    }
+    Ok(())
+}
+
+/// Day of the band containing each x, or -1. Bands are half-open `[x0, x1)`
+/// unless `closed=True`, which also admits `x == x1`. With closed bands a
+/// value on an edge shared by two bands genuinely belongs to both; it is
+/// reported for the lower band, and callers cropping each band inclusively
+/// will see it twice. Overlapping or nested bands are allowed; an x inside
+/// several goes to the band starting last. Reversed bands are swapped with a
+/// `HushdeskBandWarning`.
+#[pyfunction]
+#[pyo3(signature = (xs, bands, closed = false))]
+pub(crate) fn assign_to_band(
+    py: Python<'_>,
+    xs: Vec<f64>,
+    bands: Vec<(i32, (f64, f64))>,
+    closed: bool,
+) -> PyResult<Vec<i32>> {
+    let axis = Axis::new(ordered_bands(py, "assign_to_band", bands)?);
+    Ok(xs
+        .iter()
+        .map(|&x| {
+            axis.locate_with(x, closed)
+                .map_or(NO_BAND, |index| axis.key(index))
+        })
+        .collect())
+}
+
+/// Whether `x` lies in `band`: `[x0, x1)`, or `[x0, x1]` when `closed=True`.
+/// A reversed band is swapped with a `HushdeskBandWarning`.
+#[pyfunction]
+#[pyo3(signature = (band, x, closed = false))]
+pub(crate) fn band_contains(
+    py: Python<'_>,
+    band: (f64, f64),
+    x: f64,
+    closed: bool,
+) -> PyResult<bool> {
+    let ((x0, x1), swapped) = ordered_band(band);
+    warn_swapped(py, "band_contains", usize::from(swapped))?;
+    Ok(x >= x0 && (x < x1 || (closed && x == x1)))
+}
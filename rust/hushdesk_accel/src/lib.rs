@@ -1,39 +1,144 @@
+use numpy::PyArray1;
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
 use std::collections::BTreeMap;
 
-const CENTER_MERGE_EPSILON: f64 = 2.0;
-const MIN_BAND_WIDTH: f64 = 5.0;
+use crate::arrays::FloatArray;
+use crate::errors::{HushdeskShapeError, HushdeskValueError};
+use crate::nan::NanPolicy;
 
+mod arrays;
+mod bands;
+mod errors;
+mod grid;
+mod nan;
+mod pipeline;
+mod points;
+mod profile;
+mod stitch;
+
+const MIN_MAD_PX: f64 = 1.0;
+// Default tolerance for collapsing near-identical sorted centers.
+pub(crate) const DEDUP_EPS: f64 = 1e-6;
+// Gaps at or below this are jitter within a row, not line pitch.
+const ROW_JITTER_PX: f64 = 2.0;
+// Bin size used when the points carry no usable line pitch.
+const DEFAULT_BIN_PX: f64 = 4.0;
+// Suggested bins are this fraction of the line pitch.
+const BIN_PITCH_DIVISOR: f64 = 3.0;
+
+/// Cluster points into `bin_px` bins and return sorted centers. A non-zero
+/// `trim_frac` (in `[0, 0.5)`) drops that fraction of each cluster's lowest
+/// and highest members before averaging; clusters too small to trim keep
+/// the plain mean. Sorted centers within `dedup_eps` of each other collapse
+/// into one.
+///
+/// `soft=True` replaces hard rounding with linear bin sharing: a point at
+/// `u = y / bin_px` gives weight `1 - frac(u)` to bin `floor(u)` and
+/// `frac(u)` to bin `floor(u) + 1`. Each bin's center is the weighted mean of
+/// its contributions, and sorted bin centers closer than `bin_px / 2` merge
+/// by their total weight, so a row straddling a bin boundary stays one
+/// center. `trim_frac` is not supported with `soft`.
+///
+/// `nan_policy` is `"skip"` (drop non-finite points), `"error"`, or `"zero"`.
 #[pyfunction]
-fn y_cluster(points: Vec<f64>, bin_px: i32) -> PyResult<Vec<f64>> {
-    if points.is_empty() {
-        return Ok(Vec::new());
+#[pyo3(signature = (
+    points,
+    bin_px,
+    trim_frac = 0.0,
+    dedup_eps = DEDUP_EPS,
+    soft = false,
+    nan_policy = "skip",
+))]
+fn y_cluster(
+    points: Vec<f64>,
+    bin_px: i32,
+    trim_frac: f64,
+    dedup_eps: f64,
+    soft: bool,
+    nan_policy: &str,
+) -> PyResult<Vec<f64>> {
+    let points = NanPolicy::parse(nan_policy)?.apply(&points)?;
+    if !(0.0..0.5).contains(&trim_frac) {
+        return Err(HushdeskValueError::new_err(format!(
+            "trim_frac must be in [0, 0.5), got {trim_frac}"
+        )));
+    }
+    if !dedup_eps.is_finite() || dedup_eps < 0.0 {
+        return Err(HushdeskValueError::new_err(format!(
+            "dedup_eps must be finite and non-negative, got {dedup_eps}"
+        )));
     }
+    if soft {
+        if trim_frac > 0.0 {
+            return Err(HushdeskValueError::new_err(
+                "trim_frac cannot be combined with soft binning",
+            ));
+        }
+        let mut centers = soft_bin_centers(&points, bin_px);
+        centers.dedup_by(|a, b| (*a - *b).abs() <= dedup_eps);
+        return Ok(centers);
+    }
+    Ok(cluster_centers(&points, bin_px, trim_frac, dedup_eps))
+}
 
-    let bin_size = if bin_px <= 0 {
-        1.0
-    } else {
-        bin_px as f64
-    };
+/// Sorted centers from linearly shared bins; see `y_cluster(soft=True)`.
+fn soft_bin_centers(points: &[f64], bin_px: i32) -> Vec<f64> {
+    let bin_size = if bin_px <= 0 { 1.0 } else { bin_px as f64 };
 
-    let mut clusters: BTreeMap<i64, Vec<f64>> = BTreeMap::new();
-    for value in points {
+    let mut bins: BTreeMap<i64, (f64, f64)> = BTreeMap::new();
+    for &value in points {
         if !value.is_finite() {
             continue;
         }
-        let key = (value / bin_size).round() as i64;
-        clusters.entry(key).or_default().push(value);
+        let unit = value / bin_size;
+        let lower = unit.floor();
+        let frac = unit - lower;
+        for (key, weight) in [(lower as i64, 1.0 - frac), (lower as i64 + 1, frac)] {
+            if weight > 0.0 {
+                let bin = bins.entry(key).or_insert((0.0, 0.0));
+                bin.0 += value * weight;
+                bin.1 += weight;
+            }
+        }
     }
 
-    let mut centers: Vec<f64> = clusters
-        .into_iter()
-        .filter_map(|(_, values)| {
+    let mut weighted: Vec<(f64, f64)> = bins
+        .into_values()
+        .map(|(sum, mass)| (sum / mass, mass))
+        .collect();
+    weighted.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut merged: Vec<(f64, f64)> = Vec::with_capacity(weighted.len());
+    for (center, mass) in weighted {
+        match merged.last_mut() {
+            Some(last) if center - last.0 < bin_size / 2.0 => {
+                let total = last.1 + mass;
+                last.0 = (last.0 * last.1 + center * mass) / total;
+                last.1 = total;
+            }
+            _ => merged.push((center, mass)),
+        }
+    }
+    merged.into_iter().map(|(center, _)| center).collect()
+}
+
+pub(crate) fn cluster_centers(
+    points: &[f64],
+    bin_px: i32,
+    trim_frac: f64,
+    dedup_eps: f64,
+) -> Vec<f64> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+
+    let mut centers: Vec<f64> = bin_points(points, bin_px)
+        .into_values()
+        .filter_map(|mut values| {
             if values.is_empty() {
                 None
             } else {
-                let sum: f64 = values.iter().copied().sum();
-                Some(sum / values.len() as f64)
+                Some(trimmed_mean(&mut values, trim_frac))
             }
         })
         .collect();
@@ -42,151 +147,564 @@ fn y_cluster(points: Vec<f64>, bin_px: i32) -> PyResult<Vec<f64>> {
     centers.sort_by(|x, y| x.partial_cmp(y).unwrap_or(std::cmp::Ordering::Equal));
 
     // dedup within a small epsilon
-    const EPS: f64 = 1e-6;
-    centers.dedup_by(|a, b| (*a - *b).abs() <= EPS);
-    Ok(centers)
+    centers.dedup_by(|a, b| (*a - *b).abs() <= dedup_eps);
+    centers
 }
 
+/// Cluster `points` like `y_cluster`, then reconcile against the previous
+/// page's `previous` centers: a fresh center within `threshold` of a previous
+/// one reports the previous position, so marginal glyphs that tip a bin do
+/// not add or move rows. Previous centers with no fresh match are dropped and
+/// fresh centers beyond `threshold` of every previous one are added.
 #[pyfunction]
-fn stitch_bp(lines: Vec<String>) -> PyResult<Option<String>> {
-    if lines.len() < 2 {
-        return Ok(None);
+fn y_cluster_stable(
+    previous: Vec<f64>,
+    points: Vec<f64>,
+    bin_px: i32,
+    threshold: f64,
+) -> PyResult<Vec<f64>> {
+    let fresh = cluster_centers(&points, bin_px, 0.0, DEDUP_EPS);
+    let mut anchors: Vec<f64> = previous.into_iter().filter(|v| v.is_finite()).collect();
+    anchors.sort_by(|x, y| x.total_cmp(y));
+    if anchors.is_empty() {
+        return Ok(fresh);
     }
 
-    for (index, line) in lines.iter().enumerate() {
-        let trimmed: String = line.chars().filter(|c| !c.is_whitespace()).collect();
-        if trimmed.len() < 2 || !trimmed.ends_with('/') {
+    let mut stable: Vec<f64> = fresh
+        .into_iter()
+        .map(|center| {
+            let nearest = anchors
+                .iter()
+                .copied()
+                .min_by(|a, b| (a - center).abs().total_cmp(&(b - center).abs()))
+                .unwrap_or(center);
+            if (nearest - center).abs() <= threshold {
+                nearest
+            } else {
+                center
+            }
+        })
+        .collect();
+    stable.sort_by(|x, y| x.total_cmp(y));
+    stable.dedup();
+    Ok(stable)
+}
+
+/// Like `y_cluster`, but pair each sorted center with its member count.
+#[pyfunction]
+fn y_cluster_counts(points: Vec<f64>, bin_px: i32) -> PyResult<Vec<(f64, usize)>> {
+    Ok(cluster_counts(&points, bin_px))
+}
+
+/// Cluster like `y_cluster_counts`, then merge the closest adjacent pair
+/// until at most `max_clusters` remain. Merges use the count-weighted mean
+/// and sum the counts, so repeated merges do not drift toward sparse rows.
+#[pyfunction]
+fn y_cluster_capped(
+    points: Vec<f64>,
+    bin_px: i32,
+    max_clusters: usize,
+) -> PyResult<Vec<(f64, usize)>> {
+    if max_clusters == 0 {
+        return Err(HushdeskValueError::new_err(
+            "max_clusters must be at least 1",
+        ));
+    }
+    let mut clusters = cluster_counts(&points, bin_px);
+    while clusters.len() > max_clusters {
+        let closest = (1..clusters.len())
+            .min_by(|&a, &b| {
+                let gap_a = clusters[a].0 - clusters[a - 1].0;
+                let gap_b = clusters[b].0 - clusters[b - 1].0;
+                gap_a.total_cmp(&gap_b)
+            })
+            .unwrap_or(1);
+        let (right, right_count) = clusters.remove(closest);
+        let left = &mut clusters[closest - 1];
+        let total = left.1 + right_count;
+        left.0 = (left.0 * left.1 as f64 + right * right_count as f64) / total as f64;
+        left.1 = total;
+    }
+    Ok(clusters)
+}
+
+type CountArrays<'py> = (Bound<'py, PyArray1<f64>>, Bound<'py, PyArray1<i64>>);
+
+/// Numpy form of `y_cluster_counts`: aligned `(centers f64, counts i64)`
+/// arrays ready for vectorized filtering.
+#[pyfunction]
+fn y_cluster_counts_np<'py>(
+    py: Python<'py>,
+    points: FloatArray<'py>,
+    bin_px: i32,
+) -> PyResult<CountArrays<'py>> {
+    let (centers, counts): (Vec<f64>, Vec<i64>) = cluster_counts(&points.values(), bin_px)
+        .into_iter()
+        .map(|(center, count)| (center, count as i64))
+        .unzip();
+    Ok((
+        PyArray1::from_vec_bound(py, centers),
+        PyArray1::from_vec_bound(py, counts),
+    ))
+}
+
+fn bin_points(points: &[f64], bin_px: i32) -> BTreeMap<i64, Vec<f64>> {
+    let bin_size = if bin_px <= 0 { 1.0 } else { bin_px as f64 };
+
+    let mut clusters: BTreeMap<i64, Vec<f64>> = BTreeMap::new();
+    for &value in points {
+        if !value.is_finite() {
             continue;
         }
-        let prefix = &trimmed[..trimmed.len() - 1];
-        if prefix.len() < 2 || prefix.len() > 3 || !prefix.chars().all(|c| c.is_ascii_digit()) {
-            continue;
+        let key = (value / bin_size).round() as i64;
+        clusters.entry(key).or_default().push(value);
+    }
+    clusters
+}
+
+fn cluster_counts(points: &[f64], bin_px: i32) -> Vec<(f64, usize)> {
+    let mut clusters: Vec<(f64, usize)> = bin_points(points, bin_px)
+        .into_values()
+        .map(|values| (mean(&values), values.len()))
+        .collect();
+    clusters.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut merged: Vec<(f64, usize)> = Vec::with_capacity(clusters.len());
+    for (center, count) in clusters {
+        match merged.last_mut() {
+            Some(last) if (center - last.0).abs() <= DEDUP_EPS => last.1 += count,
+            _ => merged.push((center, count)),
+        }
+    }
+    merged
+}
+
+/// Like `y_cluster`, but return `(min, center, max)` per cluster in sorted
+/// center order, e.g. for drawing row highlight rectangles.
+#[pyfunction]
+fn y_cluster_ranges(points: Vec<f64>, bin_px: i32) -> PyResult<Vec<(f64, f64, f64)>> {
+    let mut ranges: Vec<(f64, f64, f64)> = bin_points(&points, bin_px)
+        .into_values()
+        .map(|values| {
+            let lo = values.iter().copied().fold(f64::INFINITY, f64::min);
+            let hi = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            (lo, mean(&values), hi)
+        })
+        .collect();
+    ranges.sort_by(|a, b| a.1.total_cmp(&b.1));
+    Ok(ranges)
+}
+
+/// Like `y_cluster`, but place each center at the `pct`-th percentile of its
+/// members (linear interpolation between order statistics), e.g. 40 to sit
+/// on the baseline when descenders drag the mean down. `pct` is in `[0, 100]`.
+#[pyfunction]
+fn y_cluster_percentile(points: Vec<f64>, bin_px: i32, pct: f64) -> PyResult<Vec<f64>> {
+    if !(0.0..=100.0).contains(&pct) {
+        return Err(HushdeskValueError::new_err(format!(
+            "pct must be in [0, 100], got {pct}"
+        )));
+    }
+    let mut centers: Vec<f64> = bin_points(&points, bin_px)
+        .into_values()
+        .filter_map(|mut values| {
+            values.sort_by(|a, b| a.total_cmp(b));
+            percentile(&values, pct)
+        })
+        .collect();
+    centers.sort_by(|a, b| a.total_cmp(b));
+    Ok(centers)
+}
+
+/// Split an over-binned cluster at its widest internal gap when that gap
+/// exceeds `expected_gap`; otherwise return the single mean center.
+#[pyfunction]
+fn split_cluster(members: Vec<f64>, expected_gap: f64) -> PyResult<Vec<f64>> {
+    let mut values: Vec<f64> = members.into_iter().filter(|v| v.is_finite()).collect();
+    if values.is_empty() {
+        return Ok(Vec::new());
+    }
+    values.sort_by(|x, y| x.total_cmp(y));
+
+    let mut split_at = 0;
+    let mut widest = 0.0;
+    for index in 1..values.len() {
+        let gap = values[index] - values[index - 1];
+        if gap > widest {
+            widest = gap;
+            split_at = index;
         }
+    }
 
-        for candidate in lines.iter().skip(index + 1) {
-            let digits: String = candidate.chars().filter(|c| !c.is_whitespace()).collect();
-            if digits.len() < 2 || digits.len() > 3 || !digits.chars().all(|c| c.is_ascii_digit()) {
-                continue;
+    if split_at == 0 || widest <= expected_gap {
+        return Ok(vec![mean(&values)]);
+    }
+    let (lower, upper) = values.split_at(split_at);
+    Ok(vec![mean(lower), mean(upper)])
+}
+
+pub(crate) fn mean(values: &[f64]) -> f64 {
+    values.iter().copied().sum::<f64>() / values.len() as f64
+}
+
+fn trimmed_mean(values: &mut [f64], trim_frac: f64) -> f64 {
+    let trim = (values.len() as f64 * trim_frac).floor() as usize;
+    if trim == 0 || values.len() <= 2 * trim {
+        return mean(values);
+    }
+    values.sort_by(|x, y| x.total_cmp(y));
+    mean(&values[trim..values.len() - trim])
+}
+
+/// Cluster sorted points by scatter instead of a fixed bin: a point joins
+/// the current group while it lies within `k` median absolute deviations of
+/// the group's median (the deviation is floored at `MIN_MAD_PX`). Groups
+/// with fewer than `min_size` members are dropped as noise.
+#[pyfunction]
+#[pyo3(signature = (points, k = 3.0, min_size = 1))]
+fn y_cluster_mad(points: Vec<f64>, k: f64, min_size: usize) -> PyResult<Vec<f64>> {
+    if !k.is_finite() || k <= 0.0 {
+        return Err(HushdeskValueError::new_err(format!(
+            "k must be positive, got {k}"
+        )));
+    }
+    let mut values: Vec<f64> = points.into_iter().filter(|v| v.is_finite()).collect();
+    values.sort_by(|x, y| x.total_cmp(y));
+
+    let mut centers = Vec::new();
+    let mut group: Vec<f64> = Vec::new();
+    for value in values {
+        if let Some(center) = median(&group) {
+            let spread = median_abs_deviation(&group, center).max(MIN_MAD_PX);
+            if (value - center).abs() > k * spread {
+                if group.len() >= min_size {
+                    centers.push(center);
+                }
+                group.clear();
             }
-            return Ok(Some(format!("{}/{}", prefix, digits)));
+        }
+        group.push(value);
+    }
+    if let Some(center) = median(&group) {
+        if group.len() >= min_size {
+            centers.push(center);
         }
     }
+    Ok(centers)
+}
 
-    Ok(None)
+/// Typical line height: the median gap between consecutive sorted row
+/// centers, or None with fewer than two centers.
+#[pyfunction]
+fn estimate_line_height(centers: Vec<f64>) -> PyResult<Option<f64>> {
+    let mut gaps = consecutive_gaps(&centers);
+    gaps.sort_by(|x, y| x.total_cmp(y));
+    Ok(median(&gaps))
 }
 
+/// Gaps between consecutive sorted row centers; empty for fewer than two.
+/// Tight, uniform gaps suggest a ruled form, scattered ones free text.
 #[pyfunction]
-fn select_bands(py: Python<'_>, centers: Vec<(i32, f64)>, page_w: f64) -> PyResult<Py<PyDict>> {
+fn row_gaps(centers: Vec<f64>) -> PyResult<Vec<f64>> {
+    Ok(consecutive_gaps(&centers))
+}
+
+/// Index into `centers` of the closest center to each `query` value, by
+/// binary search over the sorted centers; `centers` need not be sorted.
+/// Ties, including duplicate centers, go to the lower index.
+#[pyfunction]
+fn nearest_center(query: Vec<f64>, centers: Vec<f64>) -> PyResult<Vec<usize>> {
     if centers.is_empty() {
-        return Ok(PyDict::new(py).into());
+        return Err(HushdeskValueError::new_err("centers must not be empty"));
+    }
+    if let Some(value) = query.iter().chain(&centers).find(|v| !v.is_finite()) {
+        return Err(HushdeskValueError::new_err(format!(
+            "nearest_center needs finite values, got {value}"
+        )));
     }
+    let mut order: Vec<usize> = (0..centers.len()).collect();
+    order.sort_by(|&a, &b| centers[a].total_cmp(&centers[b]).then(a.cmp(&b)));
+    // the first (lowest-index) entry of the run of centers equal to `value`
+    let first_at = |value: f64| order[order.partition_point(|&i| centers[i] < value)];
 
-    let mut per_day: BTreeMap<i32, Vec<f64>> = BTreeMap::new();
-    for (day, center) in centers {
-        if !center.is_finite() {
-            continue;
-        }
-        per_day.entry(day).or_default().push(center);
+    Ok(query
+        .iter()
+        .filter_map(|&value| {
+            let above = order.partition_point(|&i| centers[i] < value);
+            let lower = above.checked_sub(1).map(|i| first_at(centers[order[i]]));
+            let upper = order.get(above).copied();
+            let distance = |index: usize| (centers[index] - value).abs();
+            [lower, upper]
+                .into_iter()
+                .flatten()
+                .min_by(|&a, &b| distance(a).total_cmp(&distance(b)).then(a.cmp(&b)))
+        })
+        .collect())
+}
+
+/// Suggest a `bin_px` for `y_cluster` without clustering: the median gap
+/// between sorted points that exceeds `ROW_JITTER_PX` is taken as the line
+/// pitch, and the suggestion is a third of it, floored to a whole pixel and
+/// at least 1. Points with no such gap get `DEFAULT_BIN_PX`.
+#[pyfunction]
+fn suggest_bin_px(points: Vec<f64>) -> PyResult<f64> {
+    Ok(suggested_bin(&points))
+}
+
+/// Cluster with the bin size from `suggest_bin_px`, returning `(bin_px,
+/// centers)` so the choice can be logged.
+#[pyfunction]
+fn y_cluster_auto(points: Vec<f64>) -> PyResult<(i32, Vec<f64>)> {
+    let bin_px = suggested_bin(&points) as i32;
+    Ok((bin_px, cluster_centers(&points, bin_px, 0.0, DEDUP_EPS)))
+}
+
+fn suggested_bin(points: &[f64]) -> f64 {
+    let mut pitches: Vec<f64> = consecutive_gaps(points)
+        .into_iter()
+        .filter(|gap| *gap > ROW_JITTER_PX)
+        .collect();
+    pitches.sort_by(|x, y| x.total_cmp(y));
+    match median(&pitches) {
+        Some(pitch) => (pitch / BIN_PITCH_DIVISOR).floor().max(1.0),
+        None => DEFAULT_BIN_PX,
     }
+}
 
-    let mut averaged: Vec<(i32, f64)> = per_day
+/// Rough cap height per cluster, aligned with the sorted cluster centers.
+/// Each estimate is the members' measured vertical spread, capped at the
+/// distance to the nearest neighboring row; a lone row reports its spread.
+/// A cluster with fewer than two finite members has no spread to measure
+/// and reports None, though it still counts as a neighbor.
+#[pyfunction]
+fn estimate_cap_heights(clusters: Vec<Vec<f64>>) -> PyResult<Vec<Option<f64>>> {
+    let mut rows: Vec<(f64, Option<f64>)> = clusters
         .into_iter()
-        .filter_map(|(day, values)| {
-            if values.is_empty() {
-                None
-            } else {
-                let sum: f64 = values.iter().copied().sum();
-                Some((day, sum / values.len() as f64))
+        .filter_map(|members| {
+            let finite: Vec<f64> = members.into_iter().filter(|v| v.is_finite()).collect();
+            if finite.is_empty() {
+                return None;
             }
+            let lo = finite.iter().copied().fold(f64::INFINITY, f64::min);
+            let hi = finite.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            Some((mean(&finite), (finite.len() > 1).then_some(hi - lo)))
         })
         .collect();
+    rows.sort_by(|a, b| a.0.total_cmp(&b.0));
 
-    averaged.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
-
-    let mut merged: Vec<(i32, f64)> = Vec::new();
-    if let Some(first) = averaged.first().cloned() {
-        let mut group: Vec<(i32, f64)> = vec![first];
-        for entry in averaged.into_iter().skip(1) {
-            if let Some((_, last_center)) = group.last() {
-                if (entry.1 - *last_center).abs() <= CENTER_MERGE_EPSILON {
-                    group.push(entry);
-                } else {
-                    merged.extend(collapse_center_group(&group));
-                    group = vec![entry];
-                }
-            }
-        }
-        merged.extend(collapse_center_group(&group));
-    }
-
-    let mut bands: Vec<(i32, (f64, f64))> = Vec::new();
-    let count = merged.len();
-    for (index, (day, center_x)) in merged.iter().enumerate() {
-        let mut x0;
-        let mut x1;
-        if count == 1 {
-            x0 = 0.0;
-            x1 = page_w;
-        } else if index == 0 {
-            let next_center = merged.get(index + 1).map(|(_, c)| *c).unwrap_or(*center_x);
-            let delta = (next_center - *center_x) / 2.0;
-            x0 = *center_x - delta;
-            x1 = *center_x + delta;
-        } else if index == count - 1 {
-            let prev_center = merged.get(index - 1).map(|(_, c)| *c).unwrap_or(*center_x);
-            let delta = (*center_x - prev_center) / 2.0;
-            x0 = *center_x - delta;
-            x1 = *center_x + delta;
-        } else {
-            let prev_center = merged.get(index - 1).map(|(_, c)| *c).unwrap_or(*center_x);
-            let next_center = merged.get(index + 1).map(|(_, c)| *c).unwrap_or(*center_x);
-            x0 = *center_x - (*center_x - prev_center) / 2.0;
-            x1 = *center_x + (next_center - *center_x) / 2.0;
-        }
+    let heights = (0..rows.len())
+        .map(|index| {
+            let (center, spread) = rows[index];
+            let prev = index.checked_sub(1).map(|i| center - rows[i].0);
+            let next = rows.get(index + 1).map(|row| row.0 - center);
+            let pitch = prev
+                .unwrap_or(f64::INFINITY)
+                .min(next.unwrap_or(f64::INFINITY));
+            spread.map(|spread| spread.min(pitch))
+        })
+        .collect();
+    Ok(heights)
+}
 
-        x0 = x0.max(0.0);
-        x1 = x1.min(page_w);
-        if x1 < x0 {
-            let tmp = x0;
-            x0 = x1;
-            x1 = tmp;
-        }
+/// Differences between consecutive finite values after sorting.
+pub(crate) fn consecutive_gaps(values: &[f64]) -> Vec<f64> {
+    let mut sorted: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    sorted.sort_by(|x, y| x.total_cmp(y));
+    sorted.windows(2).map(|pair| pair[1] - pair[0]).collect()
+}
 
-        let width = x1 - x0;
-        if width < MIN_BAND_WIDTH || x1 <= x0 {
-            continue;
-        }
-        bands.push((*day, (x0, x1)));
+/// Median of already-sorted values.
+pub(crate) fn median(sorted: &[f64]) -> Option<f64> {
+    let len = sorted.len();
+    if len == 0 {
+        return None;
+    }
+    if len % 2 == 1 {
+        Some(sorted[len / 2])
+    } else {
+        Some((sorted[len / 2 - 1] + sorted[len / 2]) / 2.0)
     }
+}
+
+/// `pct`-th percentile of already-sorted values, interpolating linearly
+/// between the order statistics at rank `(len - 1) * pct / 100`.
+fn percentile(sorted: &[f64], pct: f64) -> Option<f64> {
+    let last = sorted.len().checked_sub(1)?;
+    let rank = last as f64 * pct / 100.0;
+    let lower = rank.floor() as usize;
+    let upper = (lower + 1).min(last);
+    let frac = rank - lower as f64;
+    Some(sorted[lower] + frac * (sorted[upper] - sorted[lower]))
+}
+
+fn median_abs_deviation(values: &[f64], center: f64) -> f64 {
+    let mut deviations: Vec<f64> = values.iter().map(|v| (v - center).abs()).collect();
+    deviations.sort_by(|x, y| x.total_cmp(y));
+    median(&deviations).unwrap_or(0.0)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CenterMode {
+    Mean,
+    Median,
+}
 
-    let dict = PyDict::new(py);
-    for (day, (x0, x1)) in bands {
-        dict.set_item(day, (x0, x1))?;
+impl CenterMode {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "mean" => Ok(CenterMode::Mean),
+            "median" => Ok(CenterMode::Median),
+            other => Err(HushdeskValueError::new_err(format!(
+                "unknown center mode {other:?}; expected \"mean\" or \"median\""
+            ))),
+        }
     }
-    Ok(dict.into())
 }
 
-fn collapse_center_group(group: &[(i32, f64)]) -> Vec<(i32, f64)> {
-    if group.is_empty() {
-        return Vec::new();
+/// Bin points like `y_cluster`, but weight each member when computing the
+/// cluster center. `center="median"` uses the weighted median: the smallest
+/// member value whose cumulative weight reaches half the cluster total.
+/// `points` and `weights` may be float64 ndarrays, which are read in place.
+#[pyfunction]
+#[pyo3(signature = (points, weights, bin_px, center = "mean"))]
+fn y_cluster_weighted(
+    points: FloatArray<'_>,
+    weights: FloatArray<'_>,
+    bin_px: i32,
+    center: &str,
+) -> PyResult<Vec<f64>> {
+    let mode = CenterMode::parse(center)?;
+    if points.len() != weights.len() {
+        return Err(HushdeskShapeError::new_err(format!(
+            "points and weights differ in length ({} vs {})",
+            points.len(),
+            weights.len()
+        )));
     }
-    if group.len() == 1 {
-        return group.to_vec();
+    Ok(weighted_centers(
+        &points.values(),
+        &weights.values(),
+        bin_px,
+        mode,
+    ))
+}
+
+/// Area-weighted row centers: `areas` are per-glyph bounding-box areas, so
+/// large glyphs anchor the baseline more than punctuation. Points with a
+/// non-positive area are skipped.
+#[pyfunction]
+fn y_cluster_area(
+    points: FloatArray<'_>,
+    areas: FloatArray<'_>,
+    bin_px: i32,
+) -> PyResult<Vec<f64>> {
+    y_cluster_weighted(points, areas, bin_px, "mean")
+}
+
+fn weighted_centers(points: &[f64], weights: &[f64], bin_px: i32, mode: CenterMode) -> Vec<f64> {
+    let bin_size = if bin_px <= 0 { 1.0 } else { bin_px as f64 };
+
+    let mut clusters: BTreeMap<i64, Vec<(f64, f64)>> = BTreeMap::new();
+    for (&value, &weight) in points.iter().zip(weights) {
+        if !value.is_finite() || !weight.is_finite() || weight <= 0.0 {
+            continue;
+        }
+        let key = (value / bin_size).round() as i64;
+        clusters.entry(key).or_default().push((value, weight));
     }
-    let first_day = group[0].0;
-    let single_day = group.iter().all(|(day, _)| *day == first_day);
-    if single_day {
-        let avg = group.iter().map(|(_, value)| *value).sum::<f64>() / group.len() as f64;
-        vec![(first_day, avg)]
-    } else {
-        group.to_vec()
+
+    let mut centers: Vec<f64> = clusters
+        .into_values()
+        .map(|mut members| match mode {
+            CenterMode::Mean => weighted_mean(&members),
+            CenterMode::Median => weighted_median(&mut members),
+        })
+        .collect();
+    centers.sort_by(|x, y| x.total_cmp(y));
+    centers
+}
+
+fn weighted_mean(members: &[(f64, f64)]) -> f64 {
+    let total: f64 = members.iter().map(|(_, w)| *w).sum();
+    members.iter().map(|(v, w)| v * w).sum::<f64>() / total
+}
+
+fn weighted_median(members: &mut [(f64, f64)]) -> f64 {
+    members.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let half = members.iter().map(|(_, w)| *w).sum::<f64>() / 2.0;
+    let mut cumulative = 0.0;
+    for (value, weight) in members.iter() {
+        cumulative += weight;
+        if cumulative >= half {
+            return *value;
+        }
     }
+    members[members.len() - 1].0
 }
 
 #[pymodule]
-fn hushdesk_accel(py: Python<'_>, module: &PyModule) -> PyResult<()> {
+fn hushdesk_accel(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    errors::register(module)?;
     module.add_function(wrap_pyfunction!(y_cluster, module)?)?;
-    module.add_function(wrap_pyfunction!(stitch_bp, module)?)?;
-    module.add_function(wrap_pyfunction!(select_bands, module)?)?;
+    module.add_function(wrap_pyfunction!(y_cluster_stable, module)?)?;
+    module.add_function(wrap_pyfunction!(y_cluster_counts, module)?)?;
+    module.add_function(wrap_pyfunction!(y_cluster_counts_np, module)?)?;
+    module.add_function(wrap_pyfunction!(split_cluster, module)?)?;
+    module.add_function(wrap_pyfunction!(estimate_line_height, module)?)?;
+    module.add_function(wrap_pyfunction!(row_gaps, module)?)?;
+    module.add_function(wrap_pyfunction!(nearest_center, module)?)?;
+    module.add_function(wrap_pyfunction!(estimate_cap_heights, module)?)?;
+    module.add_function(wrap_pyfunction!(y_cluster_ranges, module)?)?;
+    module.add_function(wrap_pyfunction!(y_cluster_capped, module)?)?;
+    module.add_function(wrap_pyfunction!(y_cluster_percentile, module)?)?;
+    module.add_function(wrap_pyfunction!(suggest_bin_px, module)?)?;
+    module.add_function(wrap_pyfunction!(y_cluster_auto, module)?)?;
+    module.add_function(wrap_pyfunction!(y_cluster_mad, module)?)?;
+    module.add_function(wrap_pyfunction!(y_cluster_weighted, module)?)?;
+    module.add_function(wrap_pyfunction!(y_cluster_area, module)?)?;
+    module.add_function(wrap_pyfunction!(stitch::stitch_bp, module)?)?;
+    module.add_function(wrap_pyfunction!(stitch::stitch_bp_str, module)?)?;
+    module.add_function(wrap_pyfunction!(stitch::stitch_bp_explained, module)?)?;
+    module.add_function(wrap_pyfunction!(stitch::stitch_bp_batch, module)?)?;
+    module.add_function(wrap_pyfunction!(stitch::stitch_bp_parts, module)?)?;
+    module.add_function(wrap_pyfunction!(stitch::merge_fragments, module)?)?;
+    module.add_function(wrap_pyfunction!(stitch::stitch_bp_in_band, module)?)?;
+    module.add_function(wrap_pyfunction!(stitch::stitch_bp_all_cells, module)?)?;
+    module.add_function(wrap_pyfunction!(stitch::stitch_bp_with_time, module)?)?;
+    module.add_function(wrap_pyfunction!(stitch::stitch_bp_ints, module)?)?;
+    module.add_function(wrap_pyfunction!(stitch::flag_transposed_bp, module)?)?;
+    module.add_function(wrap_pyfunction!(bands::select_bands, module)?)?;
+    module.add_function(wrap_pyfunction!(bands::select_bands_list, module)?)?;
+    module.add_function(wrap_pyfunction!(bands::select_bands_weighted, module)?)?;
+    module.add_function(wrap_pyfunction!(bands::select_bands_seeded, module)?)?;
+    module.add_function(wrap_pyfunction!(bands::select_bands_batch, module)?)?;
+    module.add_function(wrap_pyfunction!(bands::band_assignments, module)?)?;
+    module.add_function(wrap_pyfunction!(bands::match_to_template, module)?)?;
+    module.add_function(wrap_pyfunction!(bands::band_overlap, module)?)?;
+    module.add_function(wrap_pyfunction!(bands::band_iou, module)?)?;
+    module.add_function(wrap_pyfunction!(bands::bands_fingerprint, module)?)?;
+    module.add_function(wrap_pyfunction!(bands::merge_centers, module)?)?;
+    module.add_function(wrap_pyfunction!(bands::split_band, module)?)?;
+    module.add_function(wrap_pyfunction!(bands::estimate_column_pitch, module)?)?;
+    module.add_function(wrap_pyfunction!(bands::regularize_centers, module)?)?;
+    module.add_function(wrap_pyfunction!(bands::assign_days, module)?)?;
+    module.add_function(wrap_pyfunction!(bands::validate_coverage, module)?)?;
+    module.add_function(wrap_pyfunction!(points::filter_y_range, module)?)?;
+    module.add_function(wrap_pyfunction!(points::transpose_points, module)?)?;
+    module.add_function(wrap_pyfunction!(points::rotate_points, module)?)?;
+    module.add_class::<grid::Grid>()?;
+    module.add_function(wrap_pyfunction!(grid::bucket_tokens, module)?)?;
+    module.add_function(wrap_pyfunction!(grid::assign_to_band, module)?)?;
+    module.add_function(wrap_pyfunction!(grid::band_contains, module)?)?;
+    module.add_class::<pipeline::ExtractConfig>()?;
+    module.add_function(wrap_pyfunction!(pipeline::extract_page, module)?)?;
+    module.add_function(wrap_pyfunction!(pipeline::page_quality, module)?)?;
+    module.add_function(wrap_pyfunction!(profile::column_gaps, module)?)?;
+    module.add_function(wrap_pyfunction!(profile::find_peaks, module)?)?;
+    module.add_function(wrap_pyfunction!(profile::find_valleys, module)?)?;
+    module.add_function(wrap_pyfunction!(profile::rle_profile, module)?)?;
+    module.add_function(wrap_pyfunction!(profile::row_profile, module)?)?;
+    module.add_function(wrap_pyfunction!(profile::occupied_runs, module)?)?;
+    module.add_function(wrap_pyfunction!(profile::segment_page_vertically, module)?)?;
     Ok(())
 }
@@ -0,0 +1,64 @@
+use pyo3::prelude::*;
+
+use crate::errors::HushdeskValueError;
+
+/// How a function treats NaN and infinite inputs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum NanPolicy {
+    /// Drop the offending value (or the record it belongs to).
+    #[default]
+    Skip,
+    /// Raise `HushdeskValueError` naming the first offending index.
+    Error,
+    /// Replace the value with 0.0 and keep going.
+    Zero,
+}
+
+impl NanPolicy {
+    pub(crate) fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "skip" => Ok(NanPolicy::Skip),
+            "error" => Ok(NanPolicy::Error),
+            "zero" => Ok(NanPolicy::Zero),
+            other => Err(HushdeskValueError::new_err(format!(
+                "unknown nan_policy {other:?}; expected \"skip\", \"error\", or \"zero\""
+            ))),
+        }
+    }
+
+    /// `Some(value)` to keep, `None` to drop, or an error under `Error`.
+    pub(crate) fn resolve(self, value: f64, index: usize) -> PyResult<Option<f64>> {
+        if value.is_finite() {
+            return Ok(Some(value));
+        }
+        match self {
+            NanPolicy::Skip => Ok(None),
+            NanPolicy::Zero => Ok(Some(0.0)),
+            NanPolicy::Error => Err(HushdeskValueError::new_err(format!(
+                "non-finite value {value} at index {index}"
+            ))),
+        }
+    }
+
+    /// Apply the policy to every element of `values`.
+    pub(crate) fn apply(self, values: &[f64]) -> PyResult<Vec<f64>> {
+        let mut kept = Vec::with_capacity(values.len());
+        for (index, &value) in values.iter().enumerate() {
+            if let Some(value) = self.resolve(value, index)? {
+                kept.push(value);
+            }
+        }
+        Ok(kept)
+    }
+
+    /// Apply the policy to the float field of `(key, value)` records.
+    pub(crate) fn apply_keyed<K: Clone>(self, records: &[(K, f64)]) -> PyResult<Vec<(K, f64)>> {
+        let mut kept = Vec::with_capacity(records.len());
+        for (index, (key, value)) in records.iter().enumerate() {
+            if let Some(value) = self.resolve(*value, index)? {
+                kept.push((key.clone(), value));
+            }
+        }
+        Ok(kept)
+    }
+}
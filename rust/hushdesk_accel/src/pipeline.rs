@@ -0,0 +1,243 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::BTreeMap;
+
+use crate::bands::{check_page_extent, layout_bands, BandOptions};
+use crate::grid::{Axis, Grid};
+use crate::profile::{occupancy, runs};
+use crate::stitch::{stitch_lines, StitchOptions};
+use crate::{cluster_centers, DEDUP_EPS};
+
+// Resolution of the body-token occupancy used to measure column gutters.
+const GUTTER_BIN_PX: i32 = 1;
+
+/// Tunables for `extract_page`.
+#[pyclass]
+#[derive(Clone, Debug)]
+pub(crate) struct ExtractConfig {
+    /// Bin size for row clustering of token y positions.
+    #[pyo3(get, set)]
+    pub(crate) row_bin_px: i32,
+    /// Day-number tokens (1-31) a row needs to be taken as the header row.
+    #[pyo3(get, set)]
+    pub(crate) min_header_days: usize,
+}
+
+impl Default for ExtractConfig {
+    fn default() -> Self {
+        ExtractConfig {
+            row_bin_px: 4,
+            min_header_days: 3,
+        }
+    }
+}
+
+#[pymethods]
+impl ExtractConfig {
+    #[new]
+    #[pyo3(signature = (row_bin_px = 4, min_header_days = 3))]
+    fn py_new(row_bin_px: i32, min_header_days: usize) -> Self {
+        ExtractConfig {
+            row_bin_px,
+            min_header_days,
+        }
+    }
+}
+
+/// Everything `extract_page` derives from one page of tokens.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PageExtraction {
+    pub(crate) rows: Vec<f64>,
+    pub(crate) header_row: Option<usize>,
+    pub(crate) bands: Vec<(i32, (f64, f64))>,
+    /// Body tokens per `(row, day)` cell, in reading order.
+    pub(crate) cells: BTreeMap<(i32, i32), Vec<String>>,
+    pub(crate) vitals: BTreeMap<(i32, i32), String>,
+    /// Body tokens (outside the header row) and how many landed in a cell.
+    pub(crate) body_tokens: usize,
+    /// x of every body token, in reading order.
+    pub(crate) body_xs: Vec<f64>,
+    pub(crate) assigned_tokens: usize,
+}
+
+/// Chain row clustering, header-day band selection, cell assignment, and
+/// BP stitching over raw `(x, y, text)` tokens. Returns a dict with `rows`
+/// (row centers), `header_row` (index into `rows`, or None), `bands`
+/// (`{day: (x0, x1)}` from the header row's day numbers), and `vitals`
+/// (`{(row, day): "sys/dia"}` keyed by row index). Rows whose centers lie
+/// closer than the minimum band width are merged before indexing. A
+/// `page_w` or `page_h` that is not positive and finite raises
+/// `HushdeskValueError`.
+#[pyfunction]
+#[pyo3(signature = (tokens, page_w, page_h, config = None))]
+pub(crate) fn extract_page(
+    py: Python<'_>,
+    tokens: Vec<(f64, f64, String)>,
+    page_w: f64,
+    page_h: f64,
+    config: Option<ExtractConfig>,
+) -> PyResult<Py<PyDict>> {
+    check_page_extent("page_w", page_w)?;
+    check_page_extent("page_h", page_h)?;
+    let config = config.unwrap_or_default();
+    let page = py.allow_threads(|| extract(&tokens, page_w, page_h, &config));
+
+    let bands = PyDict::new_bound(py);
+    for (day, span) in &page.bands {
+        bands.set_item(day, *span)?;
+    }
+    let vitals = PyDict::new_bound(py);
+    for (cell, reading) in &page.vitals {
+        vitals.set_item(*cell, reading)?;
+    }
+    let result = PyDict::new_bound(py);
+    result.set_item("rows", page.rows)?;
+    result.set_item("header_row", page.header_row)?;
+    result.set_item("bands", bands)?;
+    result.set_item("vitals", vitals)?;
+    Ok(result.unbind())
+}
+
+/// Summarize how well `extract_page` can read a page, for batch monitoring.
+/// Returns `rows` and `columns` (detected counts), `header_found`,
+/// `body_tokens`, `assigned_fraction` (share of body tokens landing in a
+/// cell, 0.0 without body tokens), `vitals` (stitched readings), and
+/// `max_gutter_width`. The gutter width comes from the `column_gaps`
+/// occupancy of body-token xs at `GUTTER_BIN_PX` bins: the widest empty run
+/// between body tokens, clipped to the span of the detected bands, so the
+/// page margins and header never count; it is 0.0 without bands. A low
+/// assignment fraction or zero columns marks a page for review. Page
+/// dimensions are validated as in `extract_page`.
+#[pyfunction]
+#[pyo3(signature = (tokens, page_w, page_h, config = None))]
+pub(crate) fn page_quality(
+    py: Python<'_>,
+    tokens: Vec<(f64, f64, String)>,
+    page_w: f64,
+    page_h: f64,
+    config: Option<ExtractConfig>,
+) -> PyResult<Py<PyDict>> {
+    check_page_extent("page_w", page_w)?;
+    check_page_extent("page_h", page_h)?;
+    let config = config.unwrap_or_default();
+    let (page, max_gutter) = py.allow_threads(|| {
+        let page = extract(&tokens, page_w, page_h, &config);
+        let max_gutter = max_gutter_width(&page, page_w);
+        (page, max_gutter)
+    });
+
+    let assigned_fraction = if page.body_tokens == 0 {
+        0.0
+    } else {
+        page.assigned_tokens as f64 / page.body_tokens as f64
+    };
+    let result = PyDict::new_bound(py);
+    result.set_item("rows", page.rows.len())?;
+    result.set_item("columns", page.bands.len())?;
+    result.set_item("header_found", page.header_row.is_some())?;
+    result.set_item("body_tokens", page.body_tokens)?;
+    result.set_item("assigned_fraction", assigned_fraction)?;
+    result.set_item("vitals", page.vitals.len())?;
+    result.set_item("max_gutter_width", max_gutter)?;
+    Ok(result.unbind())
+}
+
+/// Widest empty run between body tokens inside the banded x-span.
+fn max_gutter_width(page: &PageExtraction, page_w: f64) -> f64 {
+    let (Some(first), Some(last)) = (page.bands.first(), page.bands.last()) else {
+        return 0.0;
+    };
+    let (lo, hi) = (first.1 .0, last.1 .1);
+    let bin = GUTTER_BIN_PX as f64;
+    let counts = occupancy(&page.body_xs, page_w, GUTTER_BIN_PX);
+    runs(&counts)
+        .into_iter()
+        .filter(|&(occupied, start, end)| !occupied && start > 0 && end + 1 < counts.len())
+        .map(|(_, start, end)| {
+            let x0 = (start as f64 * bin).max(lo);
+            let x1 = ((end + 1) as f64 * bin).min(hi);
+            (x1 - x0).max(0.0)
+        })
+        .fold(0.0, f64::max)
+}
+
+pub(crate) fn extract(
+    tokens: &[(f64, f64, String)],
+    page_w: f64,
+    page_h: f64,
+    config: &ExtractConfig,
+) -> PageExtraction {
+    let mut page = PageExtraction::default();
+    let tokens: Vec<&(f64, f64, String)> = tokens
+        .iter()
+        .filter(|(x, y, _)| x.is_finite() && y.is_finite())
+        .collect();
+    let ys: Vec<f64> = tokens.iter().map(|(_, y, _)| *y).collect();
+    let row_keys: Vec<(i32, f64)> = cluster_centers(&ys, config.row_bin_px, 0.0, DEDUP_EPS)
+        .into_iter()
+        .enumerate()
+        .map(|(index, center)| (index as i32, center))
+        .collect();
+    // index rows by the layout that survived the min-gap merge, so `rows`
+    // never lists a row no token can land in
+    let row_layout = layout_bands(&row_keys, None, page_h, &BandOptions::default());
+    page.rows = row_layout
+        .centers
+        .iter()
+        .map(|(_, center)| *center)
+        .collect();
+    let row_bands: Vec<(i32, (f64, f64))> = row_layout
+        .bands
+        .into_iter()
+        .enumerate()
+        .map(|(index, (_, span))| (index as i32, span))
+        .collect();
+    let row_axis = Axis::new(row_bands.clone());
+    let row_of = |y: f64| row_axis.locate(y).map(|index| row_axis.key(index));
+
+    // the header is the row carrying the most day numbers
+    let mut day_labels: BTreeMap<i32, Vec<(i32, f64)>> = BTreeMap::new();
+    for (x, y, text) in &tokens {
+        if let (Some(row), Ok(day)) = (row_of(*y), text.trim().parse::<i32>()) {
+            if (1..=31).contains(&day) {
+                day_labels.entry(row).or_default().push((day, *x));
+            }
+        }
+    }
+    if let Some((row, labels)) = day_labels
+        .into_iter()
+        .filter(|(_, labels)| labels.len() >= config.min_header_days.max(1))
+        .max_by(|a, b| a.1.len().cmp(&b.1.len()).then(b.0.cmp(&a.0)))
+    {
+        page.header_row = Some(row as usize);
+        page.bands = layout_bands(&labels, None, page_w, &BandOptions::default()).bands;
+    }
+
+    let body_rows: Vec<(i32, (f64, f64))> = row_bands
+        .into_iter()
+        .filter(|(row, _)| Some(*row as usize) != page.header_row)
+        .collect();
+    let grid = Grid::from_bands(body_rows, page.bands.clone());
+
+    let mut ordered = tokens;
+    ordered.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.total_cmp(&b.0)));
+    for (x, y, text) in ordered {
+        if page.header_row.is_some() && row_of(*y).map(|row| row as usize) == page.header_row {
+            continue;
+        }
+        page.body_tokens += 1;
+        page.body_xs.push(*x);
+        if let Some(cell) = grid.cell(*x, *y) {
+            page.assigned_tokens += 1;
+            page.cells.entry(cell).or_default().push(text.clone());
+        }
+    }
+
+    let options = StitchOptions::default();
+    for (cell, texts) in &page.cells {
+        if let Some(reading) = stitch_lines(texts, &options) {
+            page.vitals.insert(*cell, reading);
+        }
+    }
+    page
+}
@@ -0,0 +1,59 @@
+use pyo3::prelude::*;
+
+use crate::bands::check_page_extent;
+
+/// Keep the `(x, y)` points with `y_min <= y <= y_max`, e.g. to clip the
+/// day-header band and footer before clustering body rows. Points with a
+/// non-finite coordinate are dropped.
+#[pyfunction]
+pub(crate) fn filter_y_range(
+    points: Vec<(f64, f64)>,
+    y_min: f64,
+    y_max: f64,
+) -> PyResult<Vec<(f64, f64)>> {
+    Ok(points
+        .into_iter()
+        .filter(|(x, y)| x.is_finite() && y.is_finite() && *y >= y_min && *y <= y_max)
+        .collect())
+}
+
+/// Swap each point to `(y, x)`, a reflection across the main diagonal.
+///
+/// A reflection flips handedness, so it is not how a page scanned at 90°
+/// comes back: one axis reads in reverse (the last column first, or the
+/// bottom row first). Use `rotate_points` to undo a rotated scan. Transposing
+/// twice returns the input unchanged.
+#[pyfunction]
+pub(crate) fn transpose_points(points: Vec<(f64, f64)>) -> PyResult<Vec<(f64, f64)>> {
+    Ok(points.into_iter().map(|(x, y)| (y, x)).collect())
+}
+
+/// Rotate each point a quarter turn in y-down page coordinates, e.g. to undo
+/// a page scanned at 90° before running the usual row/column flow.
+///
+/// Counter-clockwise (the default) maps `(u, v)` to `(v, extent - u)`, where
+/// `extent` is the width of the input; clockwise maps `(u, v)` to
+/// `(extent - v, u)`, where `extent` is its height. Rotating back the other
+/// way with the same `extent` returns the input, and unlike
+/// `transpose_points` both column order and top-to-bottom reading order
+/// survive. An `extent` that is not positive and finite raises
+/// `HushdeskValueError`.
+#[pyfunction]
+#[pyo3(signature = (points, extent, clockwise = false))]
+pub(crate) fn rotate_points(
+    points: Vec<(f64, f64)>,
+    extent: f64,
+    clockwise: bool,
+) -> PyResult<Vec<(f64, f64)>> {
+    check_page_extent("extent", extent)?;
+    Ok(points
+        .into_iter()
+        .map(|(u, v)| {
+            if clockwise {
+                (extent - v, u)
+            } else {
+                (v, extent - u)
+            }
+        })
+        .collect())
+}
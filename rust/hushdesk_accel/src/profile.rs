@@ -0,0 +1,239 @@
+use pyo3::prelude::*;
+
+// An empty run longer than this always separates page blocks (about two
+// blank MAR text lines).
+const BLOCK_GAP_PX: f64 = 24.0;
+// With enough interior gaps to measure row spacing, a separating run must
+// also be this many times longer than their median.
+const BLOCK_GAP_RATIO: f64 = 2.0;
+const MIN_GAP_SAMPLES: usize = 5;
+
+/// A `(y0, y1)` range on the page.
+type Span = (f64, f64);
+
+/// Count finite values per `bin_px`-wide bin over `[0, extent]`. Values
+/// outside the extent are ignored; a value exactly at `extent` lands in the
+/// last bin.
+pub(crate) fn occupancy(values: &[f64], extent: f64, bin_px: i32) -> Vec<usize> {
+    if !extent.is_finite() || extent <= 0.0 {
+        return Vec::new();
+    }
+    let bin_size = if bin_px <= 0 { 1.0 } else { bin_px as f64 };
+    let bins = (extent / bin_size).ceil().max(1.0) as usize;
+
+    let mut counts = vec![0usize; bins];
+    for &value in values {
+        if !value.is_finite() || value < 0.0 || value > extent {
+            continue;
+        }
+        let index = ((value / bin_size).floor() as usize).min(bins - 1);
+        counts[index] += 1;
+    }
+    counts
+}
+
+/// Maximal runs of empty/occupied bins; see `rle_profile`.
+pub(crate) fn runs(profile: &[usize]) -> Vec<(bool, usize, usize)> {
+    let mut result: Vec<(bool, usize, usize)> = Vec::new();
+    for (index, &count) in profile.iter().enumerate() {
+        let occupied = count > 0;
+        match result.last_mut() {
+            Some(run) if run.0 == occupied => run.2 = index,
+            _ => result.push((occupied, index, index)),
+        }
+    }
+    result
+}
+
+/// Run-length encode an occupancy profile as `(occupied, start_bin, end_bin)`
+/// runs, where `occupied` means a non-zero count and `end_bin` is inclusive.
+#[pyfunction]
+pub(crate) fn rle_profile(profile: Vec<usize>) -> PyResult<Vec<(bool, usize, usize)>> {
+    Ok(runs(&profile))
+}
+
+/// Horizontal projection: token counts per `bin_px` band of y over
+/// `[0, page_h]`. Non-finite and out-of-range ys are ignored. Pair with
+/// `find_peaks` to get row centers straight from density.
+#[pyfunction]
+pub(crate) fn row_profile(ys: Vec<f64>, page_h: f64, bin_px: i32) -> PyResult<Vec<usize>> {
+    Ok(occupancy(&ys, page_h, bin_px))
+}
+
+/// Contiguous spans of bins whose count is at least `min_count`, converted
+/// back to coordinates as `(origin + start * bin_px, origin + (end + 1) * bin_px)`.
+#[pyfunction]
+pub(crate) fn occupied_runs(
+    profile: Vec<usize>,
+    bin_px: i32,
+    origin: f64,
+    min_count: usize,
+) -> PyResult<Vec<(f64, f64)>> {
+    let bin_size = if bin_px <= 0 { 1.0 } else { bin_px as f64 };
+    let mask: Vec<usize> = profile
+        .iter()
+        .map(|&count| usize::from(count >= min_count))
+        .collect();
+    Ok(runs(&mask)
+        .into_iter()
+        .filter(|(occupied, _, _)| *occupied)
+        .map(|(_, start, end)| {
+            (
+                origin + start as f64 * bin_size,
+                origin + (end + 1) as f64 * bin_size,
+            )
+        })
+        .collect())
+}
+
+/// Split a page into `(header, body, footer)` y-ranges from the occupancy
+/// of `ys` over `[0, page_h]`. Occupied bins form blocks wherever the empty
+/// run between them is longer than `BLOCK_GAP_PX`, and, when at least
+/// `MIN_GAP_SAMPLES` interior empty runs give a row spacing, longer than
+/// `BLOCK_GAP_RATIO` times their median as well. The body is the block
+/// holding the most tokens; the header and footer span every block above
+/// and below it, or are None. A page with no tokens is all body.
+#[pyfunction]
+pub(crate) fn segment_page_vertically(
+    ys: Vec<f64>,
+    page_h: f64,
+    bin_px: i32,
+) -> PyResult<(Option<Span>, Span, Option<Span>)> {
+    let bin_size = if bin_px <= 0 { 1.0 } else { bin_px as f64 };
+    let counts = occupancy(&ys, page_h, bin_px);
+    let all_runs = runs(&counts);
+    let last = all_runs.len().saturating_sub(1);
+
+    let mut gap_lengths: Vec<usize> = all_runs
+        .iter()
+        .enumerate()
+        .filter(|(index, run)| !run.0 && *index > 0 && *index < last)
+        .map(|(_, (_, start, end))| end - start + 1)
+        .collect();
+    gap_lengths.sort_unstable();
+    let mut block_gap = BLOCK_GAP_PX;
+    if gap_lengths.len() >= MIN_GAP_SAMPLES {
+        let median = gap_lengths[gap_lengths.len() / 2] as f64 * bin_size;
+        block_gap = block_gap.max(median * BLOCK_GAP_RATIO);
+    }
+
+    // (start_bin, end_bin, tokens) per block
+    let mut blocks: Vec<(usize, usize, usize)> = Vec::new();
+    let mut pending_gap = 0;
+    for (occupied, start, end) in all_runs {
+        if !occupied {
+            pending_gap = end - start + 1;
+            continue;
+        }
+        let tokens: usize = counts[start..=end].iter().sum();
+        match blocks.last_mut() {
+            Some(block) if pending_gap as f64 * bin_size <= block_gap => {
+                block.1 = end;
+                block.2 += tokens;
+            }
+            _ => blocks.push((start, end, tokens)),
+        }
+    }
+
+    let span = |start: usize, end: usize| {
+        let y1 = ((end + 1) as f64 * bin_size).min(page_h);
+        (start as f64 * bin_size, y1)
+    };
+    // most tokens wins, the topmost block on ties
+    let Some(body) =
+        (0..blocks.len()).min_by(|&a, &b| blocks[b].2.cmp(&blocks[a].2).then(a.cmp(&b)))
+    else {
+        return Ok((None, (0.0, page_h.max(0.0)), None));
+    };
+    let header = (body > 0).then(|| span(blocks[0].0, blocks[body - 1].1));
+    let footer =
+        (body + 1 < blocks.len()).then(|| span(blocks[body + 1].0, blocks[blocks.len() - 1].1));
+    Ok((header, span(blocks[body].0, blocks[body].1), footer))
+}
+
+/// Return the x-intervals of empty-bin runs at least `min_gap_bins` long,
+/// including the leading and trailing margins.
+#[pyfunction]
+pub(crate) fn column_gaps(
+    xs: Vec<f64>,
+    page_w: f64,
+    bin_px: i32,
+    min_gap_bins: usize,
+) -> PyResult<Vec<(f64, f64)>> {
+    let bin_size = if bin_px <= 0 { 1.0 } else { bin_px as f64 };
+    let counts = occupancy(&xs, page_w, bin_px);
+
+    let gaps = runs(&counts)
+        .into_iter()
+        .filter(|(occupied, start, end)| !occupied && end - start + 1 >= min_gap_bins.max(1))
+        .map(|(_, start, end)| {
+            let x0 = start as f64 * bin_size;
+            let x1 = ((end + 1) as f64 * bin_size).min(page_w);
+            (x0, x1)
+        })
+        .collect();
+    Ok(gaps)
+}
+
+/// Pick peak bins of at least `min_height`, keeping the tallest when two are
+/// closer than `min_distance` bins. Plateaus report their midpoint and the
+/// profile is treated as zero beyond both ends.
+#[pyfunction]
+pub(crate) fn find_peaks(
+    profile: Vec<usize>,
+    min_height: usize,
+    min_distance: usize,
+) -> PyResult<Vec<usize>> {
+    let mut candidates: Vec<usize> = Vec::new();
+    let mut index = 0;
+    while index < profile.len() {
+        let value = profile[index];
+        let mut end = index;
+        while end + 1 < profile.len() && profile[end + 1] == value {
+            end += 1;
+        }
+        let left = if index == 0 { 0 } else { profile[index - 1] };
+        let right = profile.get(end + 1).copied().unwrap_or(0);
+        if value >= min_height && value > 0 && value > left && value > right {
+            candidates.push((index + end) / 2);
+        }
+        index = end + 1;
+    }
+
+    // tallest first, lower index on ties
+    candidates.sort_by(|a, b| profile[*b].cmp(&profile[*a]).then(a.cmp(b)));
+    let mut peaks: Vec<usize> = Vec::new();
+    for candidate in candidates {
+        if peaks
+            .iter()
+            .all(|peak| peak.abs_diff(candidate) >= min_distance)
+        {
+            peaks.push(candidate);
+        }
+    }
+    peaks.sort_unstable();
+    Ok(peaks)
+}
+
+/// Return cut points between segments: interior local minima whose value is
+/// at most `max_valley`. A flat valley floor reports its midpoint; floors
+/// touching either end of the profile are margins, not cuts.
+#[pyfunction]
+pub(crate) fn find_valleys(profile: Vec<usize>, max_valley: usize) -> PyResult<Vec<usize>> {
+    let mut valleys = Vec::new();
+    let mut index = 0;
+    while index < profile.len() {
+        let value = profile[index];
+        let mut end = index;
+        while end + 1 < profile.len() && profile[end + 1] == value {
+            end += 1;
+        }
+        let interior = index > 0 && end + 1 < profile.len();
+        if interior && value <= max_valley && value < profile[index - 1] && value < profile[end + 1]
+        {
+            valleys.push((index + end) / 2);
+        }
+        index = end + 1;
+    }
+    Ok(valleys)
+}
@@ -0,0 +1,571 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+use crate::errors::HushdeskValueError;
+use crate::grid::{ordered_band, warn_swapped};
+use crate::nan::NanPolicy;
+
+/// Which characters count as whitespace when normalizing fragments.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Whitespace {
+    /// Every `char::is_whitespace` character, including NBSP and thin spaces.
+    #[default]
+    Unicode,
+    /// ASCII whitespace only, so NBSP and friends survive normalization.
+    Ascii,
+}
+
+impl Whitespace {
+    pub(crate) fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "unicode" => Ok(Whitespace::Unicode),
+            "ascii" => Ok(Whitespace::Ascii),
+            other => Err(HushdeskValueError::new_err(format!(
+                "unknown whitespace mode {other:?}; expected \"unicode\" or \"ascii\""
+            ))),
+        }
+    }
+
+    fn strip(self, text: &str) -> String {
+        match self {
+            Whitespace::Unicode => text.chars().filter(|c| !c.is_whitespace()).collect(),
+            Whitespace::Ascii => text.chars().filter(|c| !c.is_ascii_whitespace()).collect(),
+        }
+    }
+}
+
+/// OCR look-alikes mapped to digits when confusion repair is enabled.
+const DEFAULT_CONFUSIONS: [(char, char); 6] = [
+    ('O', '0'),
+    ('o', '0'),
+    ('l', '1'),
+    ('I', '1'),
+    ('S', '5'),
+    ('B', '8'),
+];
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct StitchOptions {
+    pub(crate) whitespace: Whitespace,
+    /// Character repairs applied to fragments made only of digits, `/`, and
+    /// mapped look-alikes.
+    pub(crate) confusions: Option<HashMap<char, char>>,
+    /// Skip pairs that look like bordered-field artifacts (`999/999`).
+    pub(crate) reject_sentinels: bool,
+    /// Inclusive systolic and diastolic bounds checked on the parsed values.
+    pub(crate) ranges: Option<BpRanges>,
+    /// Most lines skipped between a prefix and its suffix; `None` is unbounded.
+    pub(crate) max_skip: Option<usize>,
+    /// Also look before the prefix when nothing after it completes a reading.
+    pub(crate) backward: bool,
+}
+
+/// Inclusive `(lo, hi)` bounds for systolic and diastolic values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct BpRanges {
+    pub(crate) systolic: (u32, u32),
+    pub(crate) diastolic: (u32, u32),
+}
+
+impl BpRanges {
+    fn admits(self, systolic: &str, diastolic: &str) -> bool {
+        let within = |text: &str, (lo, hi): (u32, u32)| {
+            text.parse::<u32>()
+                .is_ok_and(|value| lo <= value && value <= hi)
+        };
+        within(systolic, self.systolic) && within(diastolic, self.diastolic)
+    }
+}
+
+impl StitchOptions {
+    fn normalize(&self, text: &str) -> String {
+        let stripped = collapse_slashes(&self.whitespace.strip(text));
+        match &self.confusions {
+            Some(map)
+                if stripped
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || c == '/' || map.contains_key(&c)) =>
+            {
+                stripped
+                    .chars()
+                    .map(|c| map.get(&c).copied().unwrap_or(c))
+                    .collect()
+            }
+            _ => stripped,
+        }
+    }
+}
+
+/// Fold runs of slashes (`"120//"`) into one so doubled strokes still parse.
+fn collapse_slashes(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c == '/' && out.ends_with('/') {
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Sentinels that OCR reads off empty bordered fields.
+const SENTINELS: [&str; 2] = ["000", "999"];
+
+fn is_repeated_digit(digits: &str) -> bool {
+    let mut chars = digits.chars();
+    chars.next().is_some_and(|first| chars.all(|c| c == first))
+}
+
+fn is_implausible(systolic: &str, diastolic: &str) -> bool {
+    (is_repeated_digit(systolic) && is_repeated_digit(diastolic))
+        || SENTINELS.contains(&systolic)
+        || SENTINELS.contains(&diastolic)
+}
+
+fn confusion_map(
+    ocr: bool,
+    confusions: Option<HashMap<char, char>>,
+) -> Option<HashMap<char, char>> {
+    if !ocr {
+        return None;
+    }
+    Some(confusions.unwrap_or_else(|| DEFAULT_CONFUSIONS.into_iter().collect()))
+}
+
+/// Join a `NNN/` fragment with the next `NN`/`NNN` fragment into `"sys/dia"`.
+/// Elements are first split on embedded newlines, so line positions refer to
+/// the flattened list. `whitespace="ascii"` strips only ASCII whitespace.
+/// `ocr=True` maps look-alikes such as `O`/`l`/`S`/`B` to digits (or the
+/// `confusions` mapping when given), only in fragments made entirely of
+/// digits, `/`, and look-alikes, so `"SO"` reads as `50` but `"HR"` is kept.
+/// `reject_sentinels=True` skips pairs like `111/111` or anything with
+/// `000`/`999` and keeps scanning. `canonical=True` emits a normalized
+/// `"sys/dia"` with leading zeros removed unless `keep_leading_zeros=True`.
+/// `max_skip` bounds how many lines may sit between prefix and suffix, and
+/// `backward=True` also searches earlier lines, within the same bound, when
+/// no later line completes the reading (for reflowed OCR like `"80", "120/"`).
+/// `predicate`, a callable taking `(systolic, diastolic)` ints, must return
+/// true for a candidate to be accepted; on false the scan keeps going, and
+/// an exception it raises propagates.
+#[pyfunction]
+#[pyo3(signature = (
+    lines,
+    whitespace = "unicode",
+    ocr = false,
+    confusions = None,
+    reject_sentinels = false,
+    canonical = false,
+    keep_leading_zeros = false,
+    max_skip = None,
+    backward = false,
+    predicate = None,
+))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn stitch_bp(
+    py: Python<'_>,
+    lines: Vec<String>,
+    whitespace: &str,
+    ocr: bool,
+    confusions: Option<HashMap<char, char>>,
+    reject_sentinels: bool,
+    canonical: bool,
+    keep_leading_zeros: bool,
+    max_skip: Option<usize>,
+    backward: bool,
+    predicate: Option<PyObject>,
+) -> PyResult<Option<String>> {
+    let options = StitchOptions {
+        whitespace: Whitespace::parse(whitespace)?,
+        confusions: confusion_map(ocr, confusions),
+        reject_sentinels,
+        max_skip,
+        backward,
+        ..StitchOptions::default()
+    };
+    let reading = match predicate {
+        Some(predicate) => stitch_scan(&lines, &options, &mut |systolic, diastolic| {
+            predicate.bind(py).call1((systolic, diastolic))?.is_truthy()
+        })?
+        .ok(),
+        None => stitch_lines(&lines, &options),
+    };
+    if !canonical {
+        return Ok(reading);
+    }
+    Ok(reading.map(|reading| canonical_reading(&reading, keep_leading_zeros)))
+}
+
+/// `stitch_bp` over one multi-line string. It is split by the same rule
+/// `stitch_bp` applies to embedded newlines (`\n` or `\r\n`, no trailing
+/// empty line), so callers need not call `splitlines()` themselves.
+#[pyfunction]
+pub(crate) fn stitch_bp_str(text: &str) -> PyResult<Option<String>> {
+    Ok(stitch_lines(&[text.to_string()], &StitchOptions::default()))
+}
+
+/// Like `stitch_bp`, but also return why stitching failed: one of
+/// `"no_slash_fragment"`, `"non_numeric_prefix"` (every slash fragment had
+/// non-digits before the slash), `"no_numeric_suffix"`, or `"range_rejected"`.
+/// The reason is empty when a reading is found.
+#[pyfunction]
+pub(crate) fn stitch_bp_explained(lines: Vec<String>) -> PyResult<(Option<String>, String)> {
+    match stitch_explain(&lines, &StitchOptions::default()) {
+        Ok(reading) => Ok((Some(reading), String::new())),
+        Err(miss) => Ok((None, miss.reason().to_string())),
+    }
+}
+
+/// Stitch like `stitch_bp` and return `(systolic, diastolic, leading_zero)`,
+/// where the flag marks a fragment written with a leading zero (`"08"`).
+#[pyfunction]
+pub(crate) fn stitch_bp_parts(lines: Vec<String>) -> PyResult<Option<(u32, u32, bool)>> {
+    Ok(stitch_lines(&lines, &StitchOptions::default()).and_then(|reading| reading_parts(&reading)))
+}
+
+/// Stitch and return `(systolic, diastolic)` as integers. When both
+/// `systolic_range` and `diastolic_range` are given, pairs outside those
+/// inclusive bounds are skipped and the scan continues.
+#[pyfunction]
+#[pyo3(signature = (lines, systolic_range = None, diastolic_range = None))]
+pub(crate) fn stitch_bp_ints(
+    lines: Vec<String>,
+    systolic_range: Option<(u32, u32)>,
+    diastolic_range: Option<(u32, u32)>,
+) -> PyResult<Option<(u32, u32)>> {
+    let ranges = match (systolic_range, diastolic_range) {
+        (None, None) => None,
+        (Some(systolic), Some(diastolic)) => Some(BpRanges {
+            systolic,
+            diastolic,
+        }),
+        _ => {
+            return Err(HushdeskValueError::new_err(
+                "systolic_range and diastolic_range must be given together",
+            ))
+        }
+    };
+    let options = StitchOptions {
+        ranges,
+        ..StitchOptions::default()
+    };
+    Ok(stitch_lines(&lines, &options)
+        .and_then(|reading| reading_parts(&reading))
+        .map(|(systolic, diastolic, _)| (systolic, diastolic)))
+}
+
+/// Canonical `"sys/dia"`: no whitespace and, unless kept, no leading zeros.
+pub(crate) fn canonical_reading(reading: &str, keep_leading_zeros: bool) -> String {
+    let compact: String = reading.chars().filter(|c| !c.is_whitespace()).collect();
+    if keep_leading_zeros {
+        return compact;
+    }
+    match reading_parts(&compact) {
+        Some((systolic, diastolic, _)) => format!("{systolic}/{diastolic}"),
+        None => compact,
+    }
+}
+
+/// Indices of already-extracted `(systolic, diastolic)` pairs where the
+/// diastolic is not below the systolic, i.e. likely transpositions.
+#[pyfunction]
+pub(crate) fn flag_transposed_bp(pairs: Vec<(u32, u32)>) -> PyResult<Vec<usize>> {
+    Ok(pairs
+        .iter()
+        .enumerate()
+        .filter(|(_, (systolic, diastolic))| diastolic >= systolic)
+        .map(|(index, _)| index)
+        .collect())
+}
+
+/// Split a stitched `"sys/dia"` reading into integers plus a leading-zero flag.
+pub(crate) fn reading_parts(reading: &str) -> Option<(u32, u32, bool)> {
+    let (systolic, diastolic) = reading.split_once('/')?;
+    let leading_zero = [systolic, diastolic]
+        .iter()
+        .any(|part| part.len() > 1 && part.starts_with('0'));
+    Some((
+        systolic.parse().ok()?,
+        diastolic.parse().ok()?,
+        leading_zero,
+    ))
+}
+
+/// Stitch only the tokens whose x falls in the half-open `band = (x0, x1)`,
+/// keeping their input order, so fragments never cross a column boundary.
+/// `nan_policy` (`"skip"`, `"error"`, `"zero"`) governs non-finite xs.
+/// A reversed band is swapped with a `HushdeskBandWarning`.
+#[pyfunction]
+#[pyo3(signature = (tokens, band, nan_policy = "skip"))]
+pub(crate) fn stitch_bp_in_band(
+    py: Python<'_>,
+    tokens: Vec<(f64, String)>,
+    band: (f64, f64),
+    nan_policy: &str,
+) -> PyResult<Option<String>> {
+    let ((lo, hi), swapped) = ordered_band(band);
+    warn_swapped(py, "stitch_bp_in_band", usize::from(swapped))?;
+    let lines: Vec<String> = resolve_token_xs(tokens, NanPolicy::parse(nan_policy)?)?
+        .into_iter()
+        .filter(|(x, _)| *x >= lo && *x < hi)
+        .map(|(_, text)| text)
+        .collect();
+    Ok(stitch_lines(&lines, &StitchOptions::default()))
+}
+
+/// Run `stitch_bp` over every page's lines with the GIL released, in
+/// parallel unless `parallel=False`. Results follow input order.
+#[pyfunction]
+#[pyo3(signature = (pages, parallel = true))]
+pub(crate) fn stitch_bp_batch(
+    py: Python<'_>,
+    pages: Vec<Vec<String>>,
+    parallel: bool,
+) -> PyResult<Vec<Option<String>>> {
+    let options = StitchOptions::default();
+    let results = py.allow_threads(|| {
+        if parallel {
+            pages
+                .par_iter()
+                .map(|lines| stitch_lines(lines, &options))
+                .collect()
+        } else {
+            pages
+                .iter()
+                .map(|lines| stitch_lines(lines, &options))
+                .collect()
+        }
+    });
+    Ok(results)
+}
+
+/// Stitch a cell's BP and pair it with the first valid `HH:MM` time found in
+/// the same cell, normalized to two-digit hours.
+#[pyfunction]
+pub(crate) fn stitch_bp_with_time(
+    lines: Vec<String>,
+) -> PyResult<Option<(String, Option<String>)>> {
+    let Some(reading) = stitch_lines(&lines, &StitchOptions::default()) else {
+        return Ok(None);
+    };
+    let time = flatten_lines(&lines).into_iter().find_map(parse_time);
+    Ok(Some((reading, time)))
+}
+
+/// First `H:MM`/`HH:MM` in `text` with hours below 24 and minutes below 60.
+fn parse_time(text: &str) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    for (colon, _) in chars.iter().enumerate().filter(|(_, c)| **c == ':') {
+        let start = (0..colon)
+            .rev()
+            .take_while(|&i| chars[i].is_ascii_digit())
+            .last()
+            .unwrap_or(colon);
+        let hours: String = chars[start..colon].iter().collect();
+        let minutes: String = chars[colon + 1..].iter().take(2).collect();
+        let trailing_digit = chars.get(colon + 3).is_some_and(|c| c.is_ascii_digit());
+        if hours.is_empty()
+            || hours.len() > 2
+            || minutes.len() != 2
+            || !minutes.chars().all(|c| c.is_ascii_digit())
+            || trailing_digit
+        {
+            continue;
+        }
+        let (Ok(h), Ok(m)) = (hours.parse::<u32>(), minutes.parse::<u32>()) else {
+            continue;
+        };
+        if h < 24 && m < 60 {
+            return Some(format!("{h:02}:{m:02}"));
+        }
+    }
+    None
+}
+
+/// Stitch each grid cell's lines independently; one result per cell, in order.
+#[pyfunction]
+pub(crate) fn stitch_bp_all_cells(cells: Vec<Vec<String>>) -> PyResult<Vec<Option<String>>> {
+    let options = StitchOptions::default();
+    Ok(cells
+        .iter()
+        .map(|lines| stitch_lines(lines, &options))
+        .collect())
+}
+
+/// Join a cell's `(x, text)` fragments in reading order: tokens are sorted
+/// by x and neighbors closer than `max_gap` are joined with a space.
+/// `nan_policy` (`"skip"`, `"error"`, `"zero"`) governs non-finite xs.
+#[pyfunction]
+#[pyo3(signature = (tokens, max_gap, nan_policy = "skip"))]
+pub(crate) fn merge_fragments(
+    tokens: Vec<(f64, String)>,
+    max_gap: f64,
+    nan_policy: &str,
+) -> PyResult<Vec<String>> {
+    let mut tokens = resolve_token_xs(tokens, NanPolicy::parse(nan_policy)?)?;
+    tokens.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut merged: Vec<String> = Vec::new();
+    let mut last_x: Option<f64> = None;
+    for (x, text) in tokens {
+        match (merged.last_mut(), last_x) {
+            (Some(current), Some(prev)) if x - prev < max_gap => {
+                current.push(' ');
+                current.push_str(&text);
+            }
+            _ => merged.push(text),
+        }
+        last_x = Some(x);
+    }
+    Ok(merged)
+}
+
+fn resolve_token_xs(tokens: Vec<(f64, String)>, policy: NanPolicy) -> PyResult<Vec<(f64, String)>> {
+    let mut kept = Vec::with_capacity(tokens.len());
+    for (index, (x, text)) in tokens.into_iter().enumerate() {
+        if let Some(x) = policy.resolve(x, index)? {
+            kept.push((x, text));
+        }
+    }
+    Ok(kept)
+}
+
+pub(crate) fn stitch_lines(lines: &[String], options: &StitchOptions) -> Option<String> {
+    stitch_explain(lines, options).ok()
+}
+
+/// Why a stitch attempt found no reading.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum StitchMiss {
+    /// No fragment ended in a slash.
+    NoSlashFragment,
+    /// Every slash fragment held non-digits before the slash (`"abc/"`).
+    NonNumericPrefix,
+    /// A systolic prefix was found but nothing after it was a numeric suffix.
+    NoNumericSuffix,
+    /// Numeric fragments existed but failed the 2-3 digit checks.
+    RangeRejected,
+}
+
+impl StitchMiss {
+    pub(crate) fn reason(self) -> &'static str {
+        match self {
+            StitchMiss::NoSlashFragment => "no_slash_fragment",
+            StitchMiss::NonNumericPrefix => "non_numeric_prefix",
+            StitchMiss::NoNumericSuffix => "no_numeric_suffix",
+            StitchMiss::RangeRejected => "range_rejected",
+        }
+    }
+}
+
+pub(crate) fn stitch_explain(
+    lines: &[String],
+    options: &StitchOptions,
+) -> Result<String, StitchMiss> {
+    match stitch_scan(lines, options, &mut |_, _| Ok::<bool, Infallible>(true)) {
+        Ok(outcome) => outcome,
+        Err(never) => match never {},
+    }
+}
+
+/// `stitch_explain` with a caller check on every otherwise valid
+/// `(systolic, diastolic)` candidate: a false `accept` moves the scan on,
+/// an error from it ends the scan.
+pub(crate) fn stitch_scan<E>(
+    lines: &[String],
+    options: &StitchOptions,
+    accept: &mut impl FnMut(u32, u32) -> Result<bool, E>,
+) -> Result<Result<String, StitchMiss>, E> {
+    let lines = flatten_lines(lines);
+    let mut saw_slash = false;
+    let mut saw_numeric_prefix = false;
+    let mut range_rejected = false;
+
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = options.normalize(line);
+        if trimmed.len() < 2 || !trimmed.ends_with('/') {
+            continue;
+        }
+        saw_slash = true;
+        let prefix = &trimmed[..trimmed.len() - 1];
+        if !prefix.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        saw_numeric_prefix = true;
+        if prefix.len() < 2 || prefix.len() > 3 {
+            range_rejected = true;
+            continue;
+        }
+
+        let window = options
+            .max_skip
+            .map_or(usize::MAX, |skip| skip.saturating_add(1));
+        let forward = lines.iter().skip(index + 1).take(window);
+        if let Some(reading) = find_suffix(prefix, forward, options, accept, &mut range_rejected)? {
+            return Ok(Ok(reading));
+        }
+        if options.backward {
+            let backward = lines[..index].iter().rev().take(window);
+            if let Some(reading) =
+                find_suffix(prefix, backward, options, accept, &mut range_rejected)?
+            {
+                return Ok(Ok(reading));
+            }
+        }
+    }
+
+    Ok(Err(if !saw_slash {
+        StitchMiss::NoSlashFragment
+    } else if !saw_numeric_prefix {
+        StitchMiss::NonNumericPrefix
+    } else if range_rejected {
+        StitchMiss::RangeRejected
+    } else {
+        StitchMiss::NoNumericSuffix
+    }))
+}
+
+/// First candidate in `candidates` that completes `prefix` into a reading.
+fn find_suffix<'a, E>(
+    prefix: &str,
+    candidates: impl Iterator<Item = &'a &'a str>,
+    options: &StitchOptions,
+    accept: &mut impl FnMut(u32, u32) -> Result<bool, E>,
+    range_rejected: &mut bool,
+) -> Result<Option<String>, E> {
+    for candidate in candidates {
+        let digits = options.normalize(candidate);
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        if digits.len() < 2 || digits.len() > 3 {
+            *range_rejected = true;
+            continue;
+        }
+        if options.reject_sentinels && is_implausible(prefix, &digits) {
+            continue;
+        }
+        if options
+            .ranges
+            .is_some_and(|ranges| !ranges.admits(prefix, &digits))
+        {
+            *range_rejected = true;
+            continue;
+        }
+        if let (Ok(systolic), Ok(diastolic)) = (prefix.parse(), digits.parse()) {
+            if !accept(systolic, diastolic)? {
+                continue;
+            }
+        }
+        return Ok(Some(format!("{}/{}", prefix, digits)));
+    }
+    Ok(None)
+}
+
+/// Split inputs on embedded newlines (`\n` or `\r\n`) so a badly joined
+/// element scans as the logical lines it contains.
+pub(crate) fn flatten_lines(lines: &[String]) -> Vec<&str> {
+    lines.iter().flat_map(|line| line.lines()).collect()
+}
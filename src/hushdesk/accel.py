@@ -24,6 +24,7 @@ _CENTER_MERGE_EPSILON = 2.0
 _MIN_BAND_WIDTH = 5.0
 _BP_PREFIX_RE = re.compile(r"(?<!\d)(\d{2,3})\s*/\s*$")
 _DIGITS_ONLY_RE = re.compile(r"^\d{2,3}$")
+_SLASH_RUN_RE = re.compile(r"/{2,}")
 
 
 def _coerce_floats(values: Iterable[float]) -> List[float]:
@@ -60,10 +61,17 @@ def _y_cluster_py(points: Sequence[float], bin_px: int) -> List[float]:
 
 
 def _stitch_bp_py(lines: Sequence[str]) -> Optional[str]:
-    if len(lines) < 2:
+    # split badly joined elements so they scan as the lines they contain,
+    # drop all internal whitespace so "1 2 0 /" reads as "120/", and fold
+    # doubled strokes so "120//" reads as "120/"
+    normalized = [
+        _SLASH_RUN_RE.sub("/", "".join(part.split()))
+        for line in lines
+        for part in str(line).split("\n")
+    ]
+    if len(normalized) < 2:
         return None
 
-    normalized = [str(line).strip() for line in lines]
     for index, text in enumerate(normalized):
         if not text:
             continue
@@ -90,10 +98,35 @@ def _collapse_center_group(group: List[Tuple[int, float]]) -> List[Tuple[int, fl
     return group
 
 
+def _enforce_min_gap(
+    centers: List[Tuple[int, float]],
+    evidence: Dict[int, float],
+    min_gap: float,
+) -> List[Tuple[int, float]]:
+    # fold centers closer than min_gap into their left neighbor so the
+    # geometry never produces a degenerate pair of bands; ties keep the
+    # right-hand day, as the Rust path does
+    kept: List[List[float]] = []
+    for day, center in centers:
+        weight = evidence.get(day, 1.0)
+        if kept and center - kept[-1][1] < min_gap:
+            last = kept[-1]
+            total = last[2] + weight
+            last[1] = (last[1] * last[2] + center * weight) / total
+            if weight >= last[2]:
+                last[0] = day
+            last[2] = total
+        else:
+            kept.append([day, center, weight])
+    return [(int(day), center) for day, center, _ in kept]
+
+
 def _select_bands_py(
     centers: Sequence[Tuple[int, float]],
     page_width: float,
 ) -> Dict[int, Tuple[float, float]]:
+    if not math.isfinite(page_width) or page_width <= 0.0:
+        raise ValueError(f"page_w must be positive and finite, got {page_width}")
     if not centers:
         return {}
 
@@ -112,7 +145,7 @@ def _select_bands_py(
         by_day.setdefault(day_int, []).append(center_value)
 
     averaged = [(day, sum(values) / len(values)) for day, values in by_day.items() if values]
-    averaged.sort(key=lambda item: item[1])
+    averaged.sort(key=lambda item: (item[1], item[0]))
 
     merged: List[Tuple[int, float]] = []
     if averaged:
@@ -124,6 +157,8 @@ def _select_bands_py(
                 merged.extend(_collapse_center_group(group))
                 group = [(day, center)]
         merged.extend(_collapse_center_group(group))
+    counts = {day: float(len(values)) for day, values in by_day.items()}
+    merged = _enforce_min_gap(merged, counts, _MIN_BAND_WIDTH)
 
     count = len(merged)
     if count == 0:
@@ -192,6 +227,9 @@ def select_bands(
         try:
             mapping = select_bands_rs(values, page_width)
             return dict(mapping)  # type: ignore[arg-type]
+        except ValueError:
+            # a rejected argument is the caller's error, not a reason to fall back
+            raise
         except Exception:
             pass
     return _select_bands_py(values, page_width)
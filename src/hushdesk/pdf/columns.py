@@ -47,7 +47,14 @@ def select_audit_columns(
         rect = page.rect
         page_width = float(rect.width)
         page_height = float(rect.height)
-        day_bands = bands_from_day_centers(centers, page_width, page_height)
+        try:
+            day_bands = bands_from_day_centers(centers, page_width, page_height)
+        except ValueError:
+            # a degenerate page rect cannot hold columns; treat it like a
+            # page without a header instead of aborting the document
+            if on_page_without_header is not None:
+                on_page_without_header(page_index)
+            continue
         band = day_bands.get(target_day)
         if not band:
             continue
@@ -0,0 +1,488 @@
+"""Behavior checks for the Rust band-selection helpers."""
+
+from __future__ import annotations
+
+import random
+import warnings
+from concurrent.futures import ThreadPoolExecutor
+
+import pytest
+
+hushdesk_accel = pytest.importorskip("hushdesk_accel")
+
+
+def test_select_bands_is_stable_across_threads() -> None:
+    centers = [(day, 40.0 + day * 20.0) for day in range(1, 32)]
+    expected = hushdesk_accel.select_bands(centers, 700.0)
+
+    with ThreadPoolExecutor(max_workers=4) as pool:
+        results = list(pool.map(lambda _: hushdesk_accel.select_bands(centers, 700.0), range(16)))
+
+    assert all(result == expected for result in results)
+    assert len(expected) == 31
+
+
+def test_select_bands_warns_about_dropped_and_crowded_bands() -> None:
+    narrow = [(1, 100.0), (2, 103.0), (3, 200.0)]
+    crowded = [(1, 100.0), (2, 101.0), (3, 200.0)]
+
+    with pytest.warns(hushdesk_accel.HushdeskBandWarning, match="dropped day 1"):
+        bands = hushdesk_accel.select_bands(narrow, 612.0, warn=True)
+    with pytest.warns(hushdesk_accel.HushdeskBandWarning, match=r"days \[1, 2\]"):
+        hushdesk_accel.select_bands(crowded, 612.0, warn=True)
+
+    assert set(bands) == {2, 3}
+    assert issubclass(hushdesk_accel.HushdeskBandWarning, UserWarning)
+
+
+def test_select_bands_is_silent_by_default() -> None:
+    with warnings.catch_warnings():
+        warnings.simplefilter("error")
+        bands = hushdesk_accel.select_bands([(1, 100.0), (2, 103.0), (3, 200.0)], 612.0)
+
+    assert set(bands) == {2, 3}
+
+
+def test_select_bands_weighted_pulls_toward_wide_tokens() -> None:
+    centers = [(1, 100.0), (1, 110.0), (2, 200.0)]
+    widths = [30.0, 10.0, 20.0]
+
+    unweighted = hushdesk_accel.select_bands(centers, 612.0)
+    weighted = hushdesk_accel.select_bands_weighted(centers, widths, 612.0)
+
+    # day 1 averages to 105.0 unweighted, 102.5 weighted by width
+    assert unweighted[1] == pytest.approx((57.5, 152.5))
+    assert weighted[1] == pytest.approx((53.75, 151.25))
+    with pytest.raises(hushdesk_accel.HushdeskShapeError):
+        hushdesk_accel.select_bands_weighted(centers, widths[:2], 612.0)
+
+
+def test_select_bands_weighted_reports_total_weight_per_band() -> None:
+    centers = [(1, 100.0), (1, 110.0), (2, 200.0), (3, 203.0)]
+    widths = [30.0, 10.0, 20.0, 5.0]
+
+    totals = hushdesk_accel.select_bands_weighted(centers, widths, 612.0, with_totals=True)
+
+    # day 3 sits inside the minimum gap of day 2 and hands it its weight
+    assert set(totals) == {1, 2}
+    assert totals[1][1] == pytest.approx(40.0)
+    assert totals[2][1] == pytest.approx(25.0)
+    plain = hushdesk_accel.select_bands_weighted(centers, widths, 612.0)
+    assert totals[2][0] == pytest.approx(plain[2])
+
+
+def test_assign_days_snaps_near_misses_within_tolerance() -> None:
+    bands = [(1, (40.0, 60.0)), (2, (60.0, 80.0)), (3, (90.0, 110.0))]
+    xs = [45.0, 60.0, 83.0, 87.5, 120.0, 38.5, float("nan")]
+
+    days = hushdesk_accel.assign_days(xs, bands, 3.0)
+
+    assert days == [1, 2, 2, 3, -1, 1, -1]
+
+
+def test_validate_coverage_reports_wide_gutters_and_margins() -> None:
+    bands = [(2, (120.0, 160.0)), (1, (30.0, 80.0)), (3, (161.0, 200.0))]
+
+    gaps = hushdesk_accel.validate_coverage(bands, 240.0, 20.0)
+
+    assert gaps == pytest.approx([(0.0, 30.0), (80.0, 120.0), (200.0, 240.0)])
+    assert hushdesk_accel.validate_coverage(bands, 240.0, 50.0) == []
+
+
+def test_select_bands_weighted_evidence_scales_merge_epsilon() -> None:
+    sparse = [(1, 50.0), (2, 100.0), (3, 103.0), (4, 200.0)]
+    dense = [(1, 50.0), (2, 100.0), (3, 101.5), (4, 200.0)]
+
+    with warnings.catch_warnings(record=True) as caught:
+        warnings.simplefilter("always")
+        hushdesk_accel.select_bands_weighted(sparse, [10.0, 1.0, 1.0, 10.0], 612.0, warn=True)
+        # well-backed days 1.5px apart are held to a tighter epsilon
+        hushdesk_accel.select_bands_weighted(
+            dense, [1.0, 10.0, 10.0, 1.0], 612.0, evidence="linear", warn=True
+        )
+    # both pairs still fall under min_gap, but neither is a crowded group
+    assert [str(w.message) for w in caught if "lie within" in str(w.message)] == []
+    # thinly backed days 3px apart now count as one crowded group, and the
+    # warning names the widened epsilon that grouped them
+    with pytest.warns(hushdesk_accel.HushdeskBandWarning, match=r"days \[2, 3\] .* within 4 "):
+        hushdesk_accel.select_bands_weighted(
+            sparse, [10.0, 1.0, 1.0, 10.0], 612.0, evidence="sqrt", warn=True
+        )
+    with pytest.raises(hushdesk_accel.HushdeskValueError):
+        hushdesk_accel.select_bands_weighted(sparse, [1.0] * 4, 612.0, evidence="log")
+
+
+def test_select_bands_rejects_non_positive_page_width() -> None:
+    centers = [(1, 100.0), (2, 200.0)]
+
+    for page_w in (0.0, -612.0, float("nan")):
+        with pytest.raises(hushdesk_accel.HushdeskValueError, match="page_w"):
+            hushdesk_accel.select_bands(centers, page_w)
+        with pytest.raises(hushdesk_accel.HushdeskValueError, match="page_w"):
+            hushdesk_accel.select_bands_weighted(centers, [1.0, 1.0], page_w)
+
+
+def test_select_bands_min_gap_merges_centers_before_geometry() -> None:
+    centers = [(1, 100.0), (2, 103.0), (2, 104.0), (3, 300.0)]
+
+    bands = hushdesk_accel.select_bands(centers, 612.0)
+
+    # day 2 has two tokens, so it keeps the merged center (100 + 2 * 103.5) / 3
+    assert set(bands) == {2, 3}
+    assert bands[2][1] == pytest.approx(102.0 + 1.0 / 3.0 + (300.0 - 102.0 - 1.0 / 3.0) / 2.0)
+    # without the pre-pass both near-duplicate bands are too narrow to keep
+    lone_pair = [(1, 100.0), (2, 103.0)]
+    assert hushdesk_accel.select_bands(lone_pair, 612.0, min_gap=0.0) == {}
+    assert hushdesk_accel.select_bands(lone_pair, 612.0) == {2: (0.0, 612.0)}
+
+
+def test_select_bands_reports_days_merged_for_min_gap() -> None:
+    # a 7-day chain at a 4.5px pitch folds days 1, 3, and 5 into their neighbors
+    chain = [(day, 100.0 + 4.5 * day) for day in range(1, 8)]
+
+    with pytest.warns(hushdesk_accel.HushdeskBandWarning, match="dropped day 1: .* merged into day 2"):
+        bands, rejected = hushdesk_accel.select_bands(chain, 612.0, warn=True, with_rejected=True)
+
+    absorbed = {day for day, x0, x1, width in rejected if width == 0.0}
+    assert absorbed == {1, 3, 5}
+    assert absorbed.isdisjoint(bands)
+    assert set(bands) | absorbed == set(range(1, 8))
+    with pytest.raises(hushdesk_accel.HushdeskValueError, match="merged into day 2"):
+        hushdesk_accel.select_bands(chain, 612.0, strict=True)
+    # a crowded pair the min-gap pass folded together is not reported as kept
+    with pytest.warns(hushdesk_accel.HushdeskBandWarning, match=r"merged days \[1, 2\]"):
+        hushdesk_accel.select_bands([(1, 100.0), (2, 101.0), (3, 200.0)], 612.0, warn=True)
+
+
+def test_select_bands_seeded_anchors_columns_to_header() -> None:
+    seeds = [(1, 100.0), (2, 200.0), (3, 300.0)]
+    centers = [96.0, 104.0, 210.0, 190.0, 310.0, 420.0]
+
+    bands = hushdesk_accel.select_bands_seeded(centers, seeds, 612.0)
+
+    # 420 is more than half a seed spacing past day 3 and is dropped
+    assert bands == {
+        1: pytest.approx((50.0, 150.0)),
+        2: pytest.approx((150.0, 255.0)),
+        3: pytest.approx((255.0, 365.0)),
+    }
+
+
+def test_select_bands_places_boundaries_in_gutters() -> None:
+    centers = [(1, 100.0), (2, 200.0), (3, 300.0)]
+    gutters = [(160.0, 180.0), (400.0, 420.0)]
+
+    bands = hushdesk_accel.select_bands(centers, 612.0, gutters=gutters)
+
+    # 1|2 uses the gutter at 170; 2|3 has none between them and keeps 250
+    assert bands[1] == pytest.approx((30.0, 170.0))
+    assert bands[2] == pytest.approx((170.0, 250.0))
+    assert bands[3] == pytest.approx((250.0, 350.0))
+
+
+def test_select_bands_respects_page_margins() -> None:
+    centers = [(0, 10.0), (1, 100.0), (2, 200.0), (3, 300.0), (4, 600.0)]
+
+    bands = hushdesk_accel.select_bands(centers, 612.0, left_margin=36.0, right_margin=36.0)
+
+    # days 0 and 4 sit in the margins and are dropped
+    assert bands == {
+        1: pytest.approx((36.0, 150.0)),
+        2: pytest.approx((150.0, 250.0)),
+        3: pytest.approx((250.0, 576.0)),
+    }
+    with pytest.raises(hushdesk_accel.HushdeskValueError, match="margins"):
+        hushdesk_accel.select_bands(centers, 612.0, left_margin=400.0, right_margin=300.0)
+
+
+def test_select_bands_batch_matches_per_page_calls() -> None:
+    pages = [
+        [(1, 100.0), (2, 200.0)],
+        [],
+        [(day, 40.0 + day * 20.0) for day in range(1, 32)],
+    ]
+
+    expected = [hushdesk_accel.select_bands(centers, 700.0) for centers in pages]
+
+    assert hushdesk_accel.select_bands_batch(pages, 700.0) == expected
+    assert hushdesk_accel.select_bands_batch(pages, 700.0, parallel=False) == expected
+    assert expected[1] == {}
+
+
+def test_select_bands_with_rejected_lists_narrow_bands() -> None:
+    centers = [(1, 100.0), (2, 103.0), (3, 200.0)]
+
+    bands, rejected = hushdesk_accel.select_bands(
+        centers, 612.0, min_gap=0.0, with_rejected=True
+    )
+
+    assert set(bands) == {2, 3}
+    assert rejected == [(1, pytest.approx(98.5), pytest.approx(101.5), pytest.approx(3.0))]
+    assert hushdesk_accel.select_bands(centers, 612.0, with_rejected=True)[1] == [
+        (1, 100.0, 100.0, 0.0)
+    ]
+
+
+def test_select_bands_orders_equal_centers_by_day() -> None:
+    centers = [(2, 100.0), (1, 100.0), (3, 300.0)]
+
+    for ordering in (centers, centers[::-1]):
+        bands, rejected = hushdesk_accel.select_bands(
+            ordering, 612.0, min_gap=0.0, with_rejected=True
+        )
+
+        # day 1 sorts first and gets the zero-width band; day 2 spans to 200
+        assert [day for day, *_ in rejected] == [1]
+        assert bands == {2: pytest.approx((100.0, 200.0)), 3: pytest.approx((200.0, 400.0))}
+
+
+def test_select_bands_min_columns_rejects_single_column_pages() -> None:
+    blank = [(1, 300.0), (1, 300.0)]
+
+    assert hushdesk_accel.select_bands(blank, 612.0) == {1: (0.0, 612.0)}
+    assert hushdesk_accel.select_bands(blank, 612.0, min_columns=2) == {}
+    assert len(hushdesk_accel.select_bands([(1, 100.0), (2, 200.0)], 612.0, min_columns=2)) == 2
+
+
+def test_select_bands_strict_holds_on_random_layouts() -> None:
+    rng = random.Random(159)
+
+    for _ in range(300):
+        page_w = rng.uniform(50.0, 1200.0)
+        centers = [
+            (rng.randint(1, 31), rng.uniform(0.0, page_w)) for _ in range(rng.randint(1, 40))
+        ]
+        gutters = [(x, x + rng.uniform(0.0, 30.0)) for x in (rng.uniform(0.0, page_w),)]
+        kwargs = {"min_gap": rng.choice([0.0, 5.0, 20.0])}
+        if rng.random() < 0.5:
+            kwargs["gutters"] = gutters
+        if rng.random() < 0.3:
+            kwargs["left_margin"] = page_w * 0.05
+            kwargs["right_margin"] = page_w * 0.05
+        if rng.random() < 0.3:
+            kwargs["edge_tol"] = rng.uniform(0.0, 10.0)
+
+        try:
+            bands = hushdesk_accel.select_bands(centers, page_w, strict=True, **kwargs)
+        except hushdesk_accel.HushdeskValueError as exc:
+            # merged-away days are the only failure strict may report here
+            assert "merged into day" in str(exc)
+            bands = hushdesk_accel.select_bands(centers, page_w, **kwargs)
+
+        spans = sorted(bands.values())
+        assert all(0.0 <= x0 <= x1 <= page_w for x0, x1 in spans)
+        assert all(a[1] <= b[0] + 1e-9 for a, b in zip(spans, spans[1:]))
+
+
+def test_select_bands_strict_flags_off_page_geometry() -> None:
+    off_page = [(1, -60.0), (2, -20.0), (3, 100.0)]
+
+    assert hushdesk_accel.select_bands(off_page, 612.0, min_gap=0.0)
+    with pytest.raises(hushdesk_accel.HushdeskValueError, match="outside"):
+        hushdesk_accel.select_bands(off_page, 612.0, min_gap=0.0, strict=True)
+
+
+def test_band_assignments_breaks_down_contributing_days() -> None:
+    # a stray day-2 label at 140 lands inside day 1's band
+    centers = [(1, 100.0), (1, 104.0), (2, 140.0), (2, 300.0), (2, 310.0), (3, 400.0)]
+
+    audit = hushdesk_accel.band_assignments(centers, 612.0)
+
+    assert audit[1] == (3, {1: 2, 2: 1})
+    assert audit[2] == (2, {2: 2})
+    assert audit[3] == (1, {3: 1})
+
+
+def test_select_bands_edge_tol_keeps_edge_columns() -> None:
+    # day 1's band (-1.5, 4.5) loses 1.5px to the clamp and falls under min width
+    centers = [(1, 1.5), (2, 7.5), (3, 300.0)]
+
+    clamped = hushdesk_accel.select_bands(centers, 612.0, min_gap=0.0)
+    tolerant = hushdesk_accel.select_bands(centers, 612.0, min_gap=0.0, edge_tol=2.0)
+
+    assert 1 not in clamped
+    assert tolerant[1] == pytest.approx((0.0, 4.5))
+    assert all(0.0 <= x0 <= x1 <= 612.0 for x0, x1 in tolerant.values())
+    # day 2's band (650, 750) lies wholly off the page; it must not come back
+    # as a reversed sliver past the edge
+    bands, rejected = hushdesk_accel.select_bands(
+        [(1, 600.0), (2, 700.0)], 612.0, edge_tol=1.0, with_rejected=True
+    )
+    assert 2 not in bands
+    assert rejected == [(2, 613.0, 650.0, 37.0)]
+    assert all(0.0 <= x0 < x1 <= 612.0 for x0, x1 in bands.values())
+
+
+def test_match_to_template_uses_overlap_length() -> None:
+    template = [(1, (0.0, 100.0)), (2, (100.0, 120.0)), (3, (120.0, 300.0))]
+    # band 7 is centered nearer day 2 but mostly covers day 1
+    detected = [(7, (60.0, 125.0)), (8, (195.0, 290.0)), (9, (400.0, 450.0)), (10, (98.0, 102.0))]
+
+    matches = hushdesk_accel.match_to_template(detected, template, 3.0)
+
+    assert matches == [(7, 1), (8, 3), (9, None), (10, None)]
+
+
+def test_band_overlap_and_iou_pin_boundary_semantics() -> None:
+    assert hushdesk_accel.band_overlap((0.0, 10.0), (5.0, 20.0)) == pytest.approx(5.0)
+    assert hushdesk_accel.band_overlap((0.0, 10.0), (10.0, 20.0)) == 0.0
+    assert hushdesk_accel.band_overlap((0.0, 10.0), (30.0, 40.0)) == 0.0
+    with pytest.warns(hushdesk_accel.HushdeskBandWarning):
+        assert hushdesk_accel.band_overlap((10.0, 0.0), (2.0, 4.0)) == pytest.approx(2.0)
+
+    assert hushdesk_accel.band_iou((0.0, 10.0), (5.0, 20.0)) == pytest.approx(0.25)
+    assert hushdesk_accel.band_iou((0.0, 10.0), (0.0, 10.0)) == pytest.approx(1.0)
+    assert hushdesk_accel.band_iou((0.0, 10.0), (10.0, 20.0)) == 0.0
+    assert hushdesk_accel.band_iou((5.0, 5.0), (5.0, 5.0)) == 0.0
+
+
+def test_merge_centers_exposes_grouping_before_geometry() -> None:
+    centers = [(2, 200.0), (1, 100.0), (1, 104.0), (3, 201.0), (4, float("nan"))]
+
+    merged = hushdesk_accel.merge_centers(centers, 2.0)
+
+    # days 2 and 3 group within epsilon but stay separate days
+    assert merged == [(1, pytest.approx(102.0)), (2, pytest.approx(200.0)), (3, pytest.approx(201.0))]
+    with pytest.raises(hushdesk_accel.HushdeskValueError):
+        hushdesk_accel.merge_centers(centers, -1.0)
+
+
+def test_split_band_cuts_bimodal_columns_at_the_valley() -> None:
+    band = (0.0, 100.0)
+    # two tight clusters with an empty run of bins from 30 to 70
+    bimodal = [12.0, 14.0, 16.0, 22.0, 24.0, 76.0, 78.0, 84.0, 86.0]
+    flat = [x + 2.5 for x in range(0, 100, 5)]
+
+    assert hushdesk_accel.split_band(bimodal, band, 0.5) == [
+        pytest.approx((0.0, 50.0)),
+        pytest.approx((50.0, 100.0)),
+    ]
+    assert hushdesk_accel.split_band(flat, band, 0.5) == [band]
+    assert hushdesk_accel.split_band([], band, 0.5) == [band]
+    for ratio in (float("nan"), 0.0, 1.5):
+        with pytest.raises(hushdesk_accel.HushdeskValueError, match="min_dip_ratio"):
+            hushdesk_accel.split_band(bimodal, band, ratio)
+
+
+def test_select_bands_nan_policy() -> None:
+    centers = [(1, 100.0), (2, float("inf")), (3, 300.0)]
+
+    assert set(hushdesk_accel.select_bands(centers, 612.0)) == {1, 3}
+    assert set(hushdesk_accel.select_bands(centers, 612.0, nan_policy="zero")) == {1, 2, 3}
+    with pytest.raises(hushdesk_accel.HushdeskValueError, match="index 1"):
+        hushdesk_accel.select_bands(centers, 612.0, nan_policy="error")
+
+
+def test_bands_fingerprint_is_stable_up_to_quantum() -> None:
+    bands = [(1, (50.0, 150.0)), (2, (150.0, 250.0))]
+    jittered = [(2, (150.04, 249.98)), (1, (49.97, 150.03))]
+    shifted = [(1, (50.0, 150.0)), (2, (150.0, 251.0))]
+
+    fingerprint = hushdesk_accel.bands_fingerprint(bands, 0.5)
+    assert hushdesk_accel.bands_fingerprint(jittered, 0.5) == fingerprint
+    assert hushdesk_accel.bands_fingerprint(shifted, 0.5) != fingerprint
+    assert hushdesk_accel.bands_fingerprint(bands, 0.5) == fingerprint
+    # a band given with reversed edges is the same band
+    reversed_edges = [(1, (150.0, 50.0)), (2, (150.0, 250.0))]
+    assert hushdesk_accel.bands_fingerprint(reversed_edges, 0.5) == fingerprint
+    with pytest.raises(hushdesk_accel.HushdeskValueError):
+        hushdesk_accel.bands_fingerprint(bands, 0.0)
+
+
+def test_select_bands_key_by_xrank_ignores_day_labels() -> None:
+    centers = [(7, 300.0), (3, 100.0), (3, 104.0), (12, 200.0)]
+
+    by_day = hushdesk_accel.select_bands(centers, 612.0)
+    by_rank = hushdesk_accel.select_bands(centers, 612.0, key_by="xrank")
+
+    assert list(by_rank) == [0, 1, 2]
+    assert [by_rank[rank] for rank in range(3)] == [by_day[3], by_day[12], by_day[7]]
+    with pytest.raises(hushdesk_accel.HushdeskValueError):
+        hushdesk_accel.select_bands(centers, 612.0, key_by="column")
+
+
+def test_estimate_column_pitch_uses_median_spacing() -> None:
+    assert hushdesk_accel.estimate_column_pitch([300.0, 100.0, 201.0, 400.0, 400.0]) == pytest.approx(100.0)
+    assert hushdesk_accel.estimate_column_pitch([100.0]) is None
+
+
+def test_regularize_centers_snaps_drifted_columns_only() -> None:
+    centers = [100.0, 201.5, 299.0, 400.5, 455.0]
+
+    snapped = hushdesk_accel.regularize_centers(centers, 100.0, 3.0)
+
+    # phase fit: mean offset of the four lattice columns is +0.25
+    assert snapped == pytest.approx([100.25, 200.25, 300.25, 400.25, 455.0])
+    with pytest.raises(hushdesk_accel.HushdeskValueError):
+        hushdesk_accel.regularize_centers(centers, 0.0, 3.0)
+
+
+def test_select_bands_list_matches_dict_in_x_order() -> None:
+    centers = [(4, 300.0), (2, 100.0), (3, 200.0)]
+
+    listed = hushdesk_accel.select_bands_list(centers, 612.0)
+    keyed = hushdesk_accel.select_bands(centers, 612.0)
+
+    assert [day for day, _ in listed] == [2, 3, 4]
+    assert dict(listed) == keyed
+    assert hushdesk_accel.select_bands_list([], 612.0) == []
+
+
+def test_select_bands_trim_frac_ignores_stray_centers() -> None:
+    day_one = [(1, 100.0), (1, 101.0), (1, 99.0), (1, 100.0), (1, 160.0)]
+    centers = day_one + [(2, 200.0), (2, 202.0)]
+
+    plain = hushdesk_accel.select_bands(centers, 612.0)
+    trimmed = hushdesk_accel.select_bands(centers, 612.0, trim_frac=0.2)
+
+    # the stray 160.0 pulls day 1 to 112.0; trimming keeps it at 100.33
+    assert plain[1] == pytest.approx((112.0 - 44.5, 112.0 + 44.5))
+    assert trimmed[1][1] == pytest.approx((100.0 + 1.0 / 3.0 + 201.0) / 2.0)
+    # two centers cannot lose 20% from each end, so day 2 averages as usual
+    assert trimmed[2][0] == pytest.approx(trimmed[1][1])
+    with pytest.raises(hushdesk_accel.HushdeskValueError):
+        hushdesk_accel.select_bands(centers, 612.0, trim_frac=0.5)
+
+
+def test_band_helpers_swap_reversed_bands() -> None:
+    with pytest.warns(hushdesk_accel.HushdeskBandWarning, match="validate_coverage swapped 1"):
+        gaps = hushdesk_accel.validate_coverage([(1, (80.0, 30.0)), (2, (120.0, 160.0))], 200.0, 0.0)
+    assert gaps == pytest.approx([(0.0, 30.0), (80.0, 120.0), (160.0, 200.0)])
+
+    xs = [10.0, 11.0, 12.0, 88.0, 89.0, 90.0]
+    with pytest.warns(hushdesk_accel.HushdeskBandWarning, match="split_band swapped 1"):
+        halves = hushdesk_accel.split_band(xs, (100.0, 0.0), 0.5)
+    assert halves == pytest.approx(hushdesk_accel.split_band(xs, (0.0, 100.0), 0.5))
+    assert len(halves) == 2
+
+
+@pytest.mark.parametrize("min_gap", [float("nan"), -1.0, float("inf")])
+def test_select_bands_rejects_invalid_min_gap(min_gap: float) -> None:
+    centers = [(1, 100.0), (2, 102.0)]
+
+    with pytest.raises(hushdesk_accel.HushdeskValueError, match="min_gap"):
+        hushdesk_accel.select_bands(centers, 612.0, min_gap=min_gap)
+    with pytest.raises(hushdesk_accel.HushdeskValueError, match="min_gap"):
+        hushdesk_accel.select_bands_list(centers, 612.0, min_gap=min_gap)
+
+
+def test_select_bands_list_accepts_every_select_bands_option() -> None:
+    centers = [(9, 300.0), (4, 100.0), (4, 102.0), (4, 160.0), (5, 200.0), (6, 203.0), (7, 206.0)]
+    options = dict(
+        min_gap=0.0,
+        left_margin=20.0,
+        right_margin=22.0,
+        min_columns=2,
+        strict=True,
+        edge_tol=1.0,
+        key_by="xrank",
+        trim_frac=0.34,
+    )
+
+    listed, listed_rejected = hushdesk_accel.select_bands_list(centers, 612.0, with_rejected=True, **options)
+    keyed, keyed_rejected = hushdesk_accel.select_bands(centers, 612.0, with_rejected=True, **options)
+
+    assert [rank for rank, _ in listed] == list(range(len(listed)))
+    assert dict(listed) == pytest.approx(keyed)
+    assert listed_rejected == pytest.approx(keyed_rejected)
+    assert [day for day, *_ in listed_rejected] == [6]
@@ -0,0 +1,253 @@
+"""Behavior checks for the Rust clustering helpers."""
+
+from __future__ import annotations
+
+import pytest
+
+hushdesk_accel = pytest.importorskip("hushdesk_accel")
+
+
+def test_split_cluster_splits_bimodal_members() -> None:
+    members = [100.0, 101.0, 102.0, 118.0, 119.0, 120.0]
+
+    centers = hushdesk_accel.split_cluster(members, 6.0)
+
+    assert centers == pytest.approx([101.0, 119.0])
+
+
+def test_split_cluster_keeps_single_mode() -> None:
+    members = [100.0, 102.5, 101.0, 103.0]
+
+    centers = hushdesk_accel.split_cluster(members, 6.0)
+
+    assert centers == pytest.approx([101.625])
+    assert hushdesk_accel.split_cluster([], 6.0) == []
+
+
+def test_y_cluster_weighted_mean_follows_weights() -> None:
+    points = [100.0, 102.0, 200.0]
+    weights = [1.0, 3.0, 1.0]
+
+    centers = hushdesk_accel.y_cluster_weighted(points, weights, 20)
+
+    assert centers == pytest.approx([101.5, 200.0])
+
+
+def test_y_cluster_weighted_median_with_asymmetric_weights() -> None:
+    points = [100.0, 101.0, 102.0, 103.0]
+
+    heavy_top = hushdesk_accel.y_cluster_weighted(points, [5.0, 1.0, 1.0, 1.0], 20, center="median")
+    heavy_tail = hushdesk_accel.y_cluster_weighted(points, [1.0, 1.0, 1.0, 5.0], 20, center="median")
+    # exactly half the weight reached on the second member
+    even_split = hushdesk_accel.y_cluster_weighted(points, [1.0, 1.0, 1.0, 1.0], 20, center="median")
+
+    assert heavy_top == pytest.approx([100.0])
+    assert heavy_tail == pytest.approx([103.0])
+    assert even_split == pytest.approx([101.0])
+
+
+def test_y_cluster_weighted_rejects_length_mismatch() -> None:
+    with pytest.raises(hushdesk_accel.HushdeskShapeError):
+        hushdesk_accel.y_cluster_weighted([1.0, 2.0], [1.0], 12)
+
+
+def test_y_cluster_trimmed_mean_drops_extreme_members() -> None:
+    points = [100.0, 101.0, 101.0, 101.0, 109.0]
+
+    plain = hushdesk_accel.y_cluster(points, 20)
+    trimmed = hushdesk_accel.y_cluster(points, 20, trim_frac=0.2)
+    too_small = hushdesk_accel.y_cluster([100.0, 104.0], 20, trim_frac=0.2)
+
+    assert plain == pytest.approx([102.4])
+    assert trimmed == pytest.approx([101.0])
+    assert too_small == pytest.approx([102.0])
+
+
+def test_y_cluster_rejects_out_of_range_trim_frac() -> None:
+    with pytest.raises(hushdesk_accel.HushdeskValueError):
+        hushdesk_accel.y_cluster([1.0], 12, trim_frac=0.5)
+
+
+def test_y_cluster_mad_adapts_to_scatter() -> None:
+    tight = [100.0, 100.5, 101.0]
+    loose = [200.0, 202.0, 204.0, 206.0, 208.0]
+    stray = [300.0]
+
+    centers = hushdesk_accel.y_cluster_mad(tight + loose + stray, k=3.0)
+    denoised = hushdesk_accel.y_cluster_mad(tight + loose + stray, k=3.0, min_size=2)
+
+    assert centers == pytest.approx([100.5, 204.0, 300.0])
+    assert denoised == pytest.approx([100.5, 204.0])
+
+
+def test_y_cluster_weighted_reads_numpy_arrays() -> None:
+    np = pytest.importorskip("numpy")
+    points = np.array([100.0, 102.0, 200.0])
+    weights = np.array([1.0, 3.0, 1.0])
+
+    centers = hushdesk_accel.y_cluster_weighted(points, weights, 20)
+
+    assert centers == pytest.approx([101.5, 200.0])
+    with pytest.raises(hushdesk_accel.HushdeskShapeError):
+        hushdesk_accel.y_cluster_weighted(points, weights[:2], 20)
+
+
+def test_y_cluster_counts_pairs_centers_with_sizes() -> None:
+    points = [12.0, 12.2, 24.4, 36.0, 36.1, 60.0]
+
+    pairs = hushdesk_accel.y_cluster_counts(points, 12)
+
+    assert [center for center, _ in pairs] == pytest.approx(hushdesk_accel.y_cluster(points, 12))
+    assert [count for _, count in pairs] == [2, 1, 2, 1]
+
+
+def test_y_cluster_counts_np_returns_aligned_arrays() -> None:
+    np = pytest.importorskip("numpy")
+    points = np.array([12.0, 12.2, 24.4, 36.0, 36.1, 60.0])
+
+    centers, counts = hushdesk_accel.y_cluster_counts_np(points, 12)
+
+    assert centers.dtype == np.float64
+    assert counts.dtype == np.int64
+    assert centers.tolist() == pytest.approx([12.1, 24.4, 36.05, 60.0])
+    assert counts.tolist() == [2, 1, 2, 1]
+
+
+def test_exception_hierarchy_keeps_value_error_compatibility() -> None:
+    assert issubclass(hushdesk_accel.HushdeskShapeError, hushdesk_accel.HushdeskValueError)
+    assert issubclass(hushdesk_accel.HushdeskValueError, ValueError)
+    with pytest.raises(hushdesk_accel.HushdeskValueError):
+        hushdesk_accel.y_cluster_weighted([1.0], [1.0], 12, center="mode")
+
+
+def test_y_cluster_area_skips_non_positive_areas() -> None:
+    points = [100.0, 104.0, 101.0, 103.0]
+    areas = [30.0, 10.0, 0.0, -4.0]
+
+    centers = hushdesk_accel.y_cluster_area(points, areas, 20)
+
+    assert centers == pytest.approx([101.0])
+
+
+def test_y_cluster_stable_keeps_previous_row_structure() -> None:
+    previous = [100.0, 130.0]
+    # the second row's glyphs straddle a bin edge and split into two clusters
+    points = [100.5, 101.0, 127.0, 131.5, 190.0]
+
+    fresh = hushdesk_accel.y_cluster(points, 4)
+    stable = hushdesk_accel.y_cluster_stable(previous, points, 4, 5.0)
+
+    assert len(fresh) == 4
+    assert stable == pytest.approx([100.0, 130.0, 190.0])
+    assert hushdesk_accel.y_cluster_stable([], points, 4, 5.0) == pytest.approx(fresh)
+
+
+def test_estimate_line_height_uses_median_gap() -> None:
+    centers = [160.0, 100.0, 112.0, 124.0, 137.0]
+
+    assert hushdesk_accel.estimate_line_height(centers) == pytest.approx(12.5)
+    assert hushdesk_accel.estimate_line_height([100.0]) is None
+
+
+def test_estimate_cap_heights_aligns_with_sorted_centers() -> None:
+    clusters = [[130.0, 131.0], [100.0, 109.0], [120.0]]
+
+    heights = hushdesk_accel.estimate_cap_heights(clusters)
+
+    # centers 104.5, 120.0, 130.5: spreads 9, -, 1 against pitches 15.5, 10.5, 10.5;
+    # the single-member row at 120 has no spread to measure
+    assert heights[0] == pytest.approx(9.0)
+    assert heights[1] is None
+    assert heights[2] == pytest.approx(1.0)
+    # a spread wider than the gap to the next row is capped at that gap
+    assert hushdesk_accel.estimate_cap_heights([[100.0, 115.0], [110.0]]) == [2.5, None]
+    assert hushdesk_accel.estimate_cap_heights([[10.0, 14.0]]) == pytest.approx([4.0])
+    assert hushdesk_accel.estimate_cap_heights([[10.0, float("nan")]]) == [None]
+
+
+def test_y_cluster_ranges_reports_member_extent() -> None:
+    points = [101.0, 99.0, 100.0, 140.0]
+
+    ranges = hushdesk_accel.y_cluster_ranges(points, 20)
+
+    assert ranges == pytest.approx([(99.0, 100.0, 101.0), (140.0, 140.0, 140.0)])
+    assert [center for _, center, _ in ranges] == hushdesk_accel.y_cluster(points, 20)
+
+
+def test_y_cluster_dedup_eps_collapses_near_duplicates() -> None:
+    points = [9.0, 11.0, 12.5]
+
+    assert hushdesk_accel.y_cluster(points, 2) == pytest.approx([9.0, 11.75])
+    assert hushdesk_accel.y_cluster(points, 2, dedup_eps=3.0) == pytest.approx([9.0])
+    with pytest.raises(hushdesk_accel.HushdeskValueError):
+        hushdesk_accel.y_cluster(points, 2, dedup_eps=-1.0)
+
+
+def test_y_cluster_capped_merges_by_count_weighted_mean() -> None:
+    points = [100.0, 100.0, 100.0, 110.0, 200.0]
+
+    capped = hushdesk_accel.y_cluster_capped(points, 4, 2)
+
+    # 100 (x3) and 110 (x1) are closest and merge to (300 + 110) / 4
+    assert capped == [(pytest.approx(102.5), 4), (pytest.approx(200.0), 1)]
+    assert hushdesk_accel.y_cluster_capped(points, 4, 1) == [(pytest.approx(122.0), 5)]
+    assert hushdesk_accel.y_cluster_capped(points, 4, 5) == hushdesk_accel.y_cluster_counts(points, 4)
+
+
+def test_y_cluster_soft_keeps_straddling_row_together() -> None:
+    # 9.9 and 10.1 round into different 4px bins (2 and 3)
+    points = [9.9, 10.1, 30.0]
+
+    assert hushdesk_accel.y_cluster(points, 4) == pytest.approx([9.9, 10.1, 30.0])
+    assert hushdesk_accel.y_cluster(points, 4, soft=True) == pytest.approx([10.0, 30.0])
+    with pytest.raises(hushdesk_accel.HushdeskValueError):
+        hushdesk_accel.y_cluster(points, 4, trim_frac=0.1, soft=True)
+
+
+def test_y_cluster_percentile_interpolates_order_statistics() -> None:
+    points = [100.0, 101.0, 102.0, 106.0, 200.0]
+
+    assert hushdesk_accel.y_cluster_percentile(points, 20, 50.0) == pytest.approx([101.5, 200.0])
+    assert hushdesk_accel.y_cluster_percentile(points, 20, 40.0) == pytest.approx([101.2, 200.0])
+    assert hushdesk_accel.y_cluster_percentile(points, 20, 100.0) == pytest.approx([106.0, 200.0])
+    with pytest.raises(hushdesk_accel.HushdeskValueError):
+        hushdesk_accel.y_cluster_percentile(points, 20, 101.0)
+
+
+def test_y_cluster_nan_policy() -> None:
+    points = [100.0, float("nan"), 101.0]
+
+    assert hushdesk_accel.y_cluster(points, 20) == pytest.approx([100.5])
+    assert hushdesk_accel.y_cluster(points, 20, nan_policy="zero") == pytest.approx([0.0, 100.5])
+    with pytest.raises(hushdesk_accel.HushdeskValueError, match="index 1"):
+        hushdesk_accel.y_cluster(points, 20, nan_policy="error")
+    with pytest.raises(hushdesk_accel.HushdeskValueError, match="nan_policy"):
+        hushdesk_accel.y_cluster(points, 20, nan_policy="ignore")
+
+
+def test_suggest_bin_px_and_y_cluster_auto_agree() -> None:
+    # rows every 12px with sub-pixel jitter inside each row
+    points = [100.0, 100.5, 112.0, 112.4, 124.0, 136.0, 136.8]
+
+    # pitch gaps 11.5, 11.6, 12.0 give a median of 11.6 and a bin of 3
+    assert hushdesk_accel.suggest_bin_px(points) == 3.0
+    bin_px, centers = hushdesk_accel.y_cluster_auto(points)
+    assert bin_px == 3
+    assert centers == hushdesk_accel.y_cluster(points, 3)
+    assert hushdesk_accel.suggest_bin_px([50.0, 50.5]) == 4.0
+    assert hushdesk_accel.suggest_bin_px([0.0, 60.0]) == 20.0
+
+
+def test_row_gaps_sorts_before_differencing() -> None:
+    assert hushdesk_accel.row_gaps([124.0, 100.0, 112.0, 150.0]) == pytest.approx([12.0, 12.0, 26.0])
+    assert hushdesk_accel.row_gaps([100.0]) == []
+    assert hushdesk_accel.row_gaps([]) == []
+
+
+def test_nearest_center_snaps_queries_and_breaks_ties_low() -> None:
+    centers = [130.0, 100.0, 115.0, 100.0]
+
+    assert hushdesk_accel.nearest_center([99.0, 107.5, 112.0, 200.0, 0.0], centers) == [1, 1, 2, 0, 1]
+    assert hushdesk_accel.nearest_center([122.5], centers) == [0]
+    with pytest.raises(hushdesk_accel.HushdeskValueError):
+        hushdesk_accel.nearest_center([1.0], [])
@@ -0,0 +1,130 @@
+"""Behavior checks for the Rust grid helpers."""
+
+from __future__ import annotations
+
+import pytest
+
+hushdesk_accel = pytest.importorskip("hushdesk_accel")
+
+ROWS = [(0, (100.0, 120.0)), (1, (120.0, 140.0))]
+COLS = [(2, (60.0, 80.0)), (1, (40.0, 60.0)), (3, (80.0, 100.0))]
+
+
+def test_grid_cell_of_uses_half_open_cells() -> None:
+    grid = hushdesk_accel.Grid(ROWS, COLS)
+
+    assert grid.shape() == (2, 3)
+    assert grid.cell_of(45.0, 110.0) == (0, 1)
+    assert grid.cell_of(60.0, 120.0) == (1, 2)
+    assert grid.cell_of(100.0, 110.0) is None
+    assert grid.cell_of(50.0, 99.9) is None
+
+
+def test_grid_rect_reports_cell_bounds() -> None:
+    grid = hushdesk_accel.Grid(ROWS, COLS)
+
+    assert grid.rect(1, 3) == pytest.approx((80.0, 120.0, 100.0, 140.0))
+    assert grid.rect(5, 3) is None
+
+
+def test_grid_assign_classifies_points_in_order() -> None:
+    grid = hushdesk_accel.Grid(ROWS, COLS)
+    points = [(45.0, 110.0), (80.0, 120.0), (100.0, 120.0), (59.999, 139.999)]
+
+    assert grid.assign(points) == [(0, 1), (1, 3), (-1, -1), (1, 1)]
+
+
+def test_bucket_tokens_groups_text_per_cell() -> None:
+    grid = hushdesk_accel.Grid(ROWS, COLS)
+    xs = [45.0, 70.0, 50.0, 500.0]
+    ys = [110.0, 125.0, 115.0, 110.0]
+    texts = ["120/", "0800", "80", "footer"]
+
+    buckets = hushdesk_accel.bucket_tokens(xs, ys, texts, grid)
+
+    assert buckets == {(0, 1): ["120/", "80"], (1, 2): ["0800"], (-1, -1): ["footer"]}
+    with pytest.raises(hushdesk_accel.HushdeskShapeError):
+        hushdesk_accel.bucket_tokens(xs, ys[:2], texts, grid)
+
+
+def test_filter_y_range_is_inclusive_and_drops_non_finite() -> None:
+    points = [(10.0, 40.0), (12.0, 100.0), (14.0, 250.0), (16.0, float("nan")), (float("inf"), 120.0)]
+
+    kept = hushdesk_accel.filter_y_range(points, 100.0, 250.0)
+
+    assert kept == [(12.0, 100.0), (14.0, 250.0)]
+
+
+def test_transpose_points_swaps_axes_and_round_trips() -> None:
+    points = [(10.0, 200.0), (35.5, -4.0)]
+
+    transposed = hushdesk_accel.transpose_points(points)
+
+    assert transposed == [(200.0, 10.0), (-4.0, 35.5)]
+    assert hushdesk_accel.transpose_points(transposed) == points
+
+
+def test_rotate_points_undoes_a_quarter_turn_scan() -> None:
+    # header days 1-3 over one body row on a 300x400 page, scanned turned a
+    # quarter clockwise: page (x, y) arrives as (400 - y, x)
+    page = [(100.0, 50.0), (150.0, 50.0), (200.0, 50.0), (100.0, 120.0), (200.0, 120.0)]
+    scan = [(400.0 - y, x) for x, y in page]
+
+    restored = hushdesk_accel.rotate_points(scan, 400.0)
+
+    assert restored == page
+    assert hushdesk_accel.rotate_points(restored, 400.0, clockwise=True) == scan
+    days = [(day, x) for day, (x, _) in zip((1, 2, 3), restored)]
+    bands = hushdesk_accel.select_bands(days, 300.0)
+    assert sorted(bands, key=lambda day: bands[day][0]) == [1, 2, 3]
+    # reading order: the header row comes before the body row
+    assert hushdesk_accel.y_cluster([y for _, y in restored], 4) == pytest.approx([50.0, 120.0])
+    # a transpose instead mirrors the rows, putting the header below the body
+    mirrored = hushdesk_accel.transpose_points(scan)
+    assert [y for _, y in mirrored[:3]] == [350.0] * 3
+    with pytest.raises(hushdesk_accel.HushdeskValueError, match="extent"):
+        hushdesk_accel.rotate_points(scan, 0.0)
+
+
+def test_assign_to_band_closed_admits_upper_edges() -> None:
+    bands = [(1, (0.0, 50.0)), (2, (50.0, 100.0))]
+    xs = [0.0, 50.0, 100.0, 100.5]
+
+    assert hushdesk_accel.assign_to_band(xs, bands) == [1, 2, -1, -1]
+    # the shared edge at 50 goes to the lower band when closed
+    assert hushdesk_accel.assign_to_band(xs, bands, closed=True) == [1, 1, 2, -1]
+    assert hushdesk_accel.band_contains((0.0, 50.0), 50.0) is False
+    assert hushdesk_accel.band_contains((0.0, 50.0), 50.0, closed=True) is True
+
+
+
+def test_assign_to_band_handles_nested_and_overlapping_bands() -> None:
+    nested = [(1, (0.0, 20.0)), (2, (3.0, 8.0))]
+
+    # inside both, x goes to the band starting last; past day 2 it is day 1's
+    assert hushdesk_accel.assign_to_band([5.0, 12.0, 1.0, 20.0], nested) == [2, 1, 1, -1]
+    assert hushdesk_accel.assign_to_band([8.0, 20.0], nested, closed=True) == [2, 1]
+    overlapping = [(1, (0.0, 60.0)), (2, (50.0, 100.0)), (3, (55.0, 58.0))]
+    assert hushdesk_accel.assign_to_band([52.0, 59.0, 70.0], overlapping) == [2, 2, 2]
+    # a reversed outer band takes the warning path and still holds the tail
+    with pytest.warns(hushdesk_accel.HushdeskBandWarning, match="assign_to_band swapped 1"):
+        assert hushdesk_accel.assign_to_band([12.0], [(1, (20.0, 0.0)), (2, (3.0, 8.0))]) == [1]
+    grid = hushdesk_accel.Grid([(0, (0.0, 10.0))], nested)
+    assert grid.cell_of(12.0, 5.0) == (0, 1)
+
+def test_reversed_bands_are_swapped_with_a_warning() -> None:
+    bands = [(1, (100.0, 0.0)), (2, (100.0, 200.0))]
+
+    with pytest.warns(hushdesk_accel.HushdeskBandWarning, match="assign_to_band swapped 1"):
+        assert hushdesk_accel.assign_to_band([50.0, 150.0], bands) == [1, 2]
+    with pytest.warns(hushdesk_accel.HushdeskBandWarning, match="band_contains"):
+        assert hushdesk_accel.band_contains((100.0, 0.0), 50.0)
+    with pytest.warns(hushdesk_accel.HushdeskBandWarning, match="band_overlap swapped 2"):
+        assert hushdesk_accel.band_overlap((100.0, 0.0), (150.0, 50.0)) == pytest.approx(50.0)
+    with pytest.warns(hushdesk_accel.HushdeskBandWarning, match="band_iou"):
+        assert hushdesk_accel.band_iou((100.0, 0.0), (50.0, 150.0)) == pytest.approx(1.0 / 3.0)
+    with pytest.warns(hushdesk_accel.HushdeskBandWarning, match="Grid swapped"):
+        grid = hushdesk_accel.Grid([(0, (20.0, 0.0))], [(1, (100.0, 0.0))])
+    assert grid.cell_of(50.0, 10.0) == (0, 1)
+    with pytest.warns(hushdesk_accel.HushdeskBandWarning, match="assign_days swapped 1"):
+        assert hushdesk_accel.assign_days([50.0, 150.0], bands, 0.0) == [1, 2]
@@ -3,6 +3,7 @@
 from __future__ import annotations
 
 from typing import Dict, Tuple
+from unittest.mock import patch
 
 import pytest
 
@@ -46,3 +47,69 @@ def test_select_bands_matches_python() -> None:
     for day, (x0, x1) in py_bands.items():
         assert rs_bands[day][0] == pytest.approx(x0)
         assert rs_bands[day][1] == pytest.approx(x1)
+
+
+def test_stitch_bp_splits_embedded_newlines() -> None:
+    lines = ["BP 9:00\n120 /", "80"]
+
+    py_value = accel._stitch_bp_py(lines)
+    rs_value = accel.stitch_bp_rs(lines)
+
+    assert rs_value == py_value == "120/80"
+    assert accel.stitch_bp_rs(["120/\r\n80"]) == "120/80"
+
+
+@pytest.mark.parametrize("prefix", ["1 20/", "1 2 0 /", " 12 0 / ", "\t1 20\t/"])
+def test_stitch_bp_reassembles_spaced_prefix(prefix: str) -> None:
+    lines = [prefix, "8 0"]
+
+    assert accel._stitch_bp_py(lines) == "120/80"
+    assert accel.stitch_bp_rs(lines) == "120/80"
+
+
+def test_stitch_bp_folds_double_slash_and_rejects_mid_number_slash() -> None:
+    for lines, expected in [
+        (["120//", "80"], "120/80"),
+        (["120 / /", "80"], "120/80"),
+        (["12/0/", "80"], None),
+        (["1/20/", "80"], None),
+        (["120/", "80"], "120/80"),
+    ]:
+        assert accel._stitch_bp_py(lines) == expected
+        assert accel.stitch_bp_rs(lines) == expected
+
+
+def test_select_bands_min_gap_matches_python() -> None:
+    centers = [(1, 100.0), (2, 103.0), (2, 104.0), (3, 300.0)]
+
+    py_bands = accel._select_bands_py(centers, 612.0)
+    rs_bands: Dict[int, Tuple[float, float]] = dict(accel.select_bands_rs(centers, 612.0))
+
+    assert set(rs_bands) == set(py_bands) == {2, 3}
+    for day, band in py_bands.items():
+        assert rs_bands[day] == pytest.approx(band)
+
+
+def test_select_bands_duplicate_centers_ignore_input_order() -> None:
+    centers = [(2, 100.0), (1, 100.0), (3, 300.0)]
+    flipped = [(1, 100.0), (2, 100.0), (3, 300.0)]
+
+    expected = accel._select_bands_py(flipped, 612.0)
+
+    assert accel._select_bands_py(centers, 612.0) == expected
+    assert dict(accel.select_bands_rs(centers, 612.0)) == expected
+    assert dict(accel.select_bands_rs(flipped, 612.0)) == expected
+    # the min-gap merge keeps the right-hand (higher) day on equal evidence
+    assert set(expected) == {2, 3}
+
+
+@pytest.mark.parametrize("page_width", [0.0, -612.0, float("nan")])
+def test_select_bands_wrapper_rejects_bad_page_width(page_width: float) -> None:
+    centers = [(1, 100.0), (2, 200.0)]
+
+    with patch.object(accel, "USE_RUST", True):
+        with pytest.raises(ValueError):
+            accel.select_bands(centers, page_width)
+    with patch.object(accel, "USE_RUST", False):
+        with pytest.raises(ValueError):
+            accel.select_bands(centers, page_width)
@@ -0,0 +1,91 @@
+"""Behavior checks for the Rust one-call page pipeline."""
+
+from __future__ import annotations
+
+import pytest
+
+hushdesk_accel = pytest.importorskip("hushdesk_accel")
+
+TOKENS = [
+    (100.0, 50.0, "1"),
+    (150.0, 50.0, "2"),
+    (200.0, 50.0, "3"),
+    (98.0, 100.0, "120/"),
+    (102.0, 101.0, "80"),
+    (150.0, 100.0, "135/"),
+    (152.0, 101.0, "85"),
+    (200.0, 100.0, "HR"),
+]
+
+
+def test_extract_page_chains_rows_bands_and_stitching() -> None:
+    page = hushdesk_accel.extract_page(TOKENS, 300.0, 400.0)
+
+    assert page["rows"] == pytest.approx([50.0, 100.4])
+    assert page["header_row"] == 0
+    assert sorted(page["bands"]) == [1, 2, 3]
+    assert page["bands"][2] == pytest.approx((125.0, 175.0))
+    assert page["vitals"] == {(1, 1): "120/80", (1, 2): "135/85"}
+
+
+def test_extract_page_config_controls_header_detection() -> None:
+    config = hushdesk_accel.ExtractConfig(min_header_days=4)
+
+    page = hushdesk_accel.extract_page(TOKENS, 300.0, 400.0, config)
+
+    assert config.row_bin_px == 4
+    assert page["header_row"] is None
+    assert page["bands"] == {}
+    assert page["vitals"] == {}
+
+
+def test_page_quality_summarizes_extraction() -> None:
+    quality = hushdesk_accel.page_quality(TOKENS, 300.0, 400.0)
+
+    assert quality["rows"] == 2
+    assert quality["columns"] == 3
+    assert quality["header_found"] is True
+    assert quality["body_tokens"] == 5
+    assert quality["assigned_fraction"] == pytest.approx(1.0)
+    assert quality["vitals"] == 2
+    # body xs 98..102, 150..152, 200: the 103-150 run is the widest gutter
+    assert quality["max_gutter_width"] == pytest.approx(47.0)
+
+
+def test_page_quality_flags_pages_without_columns() -> None:
+    config = hushdesk_accel.ExtractConfig(min_header_days=4)
+
+    quality = hushdesk_accel.page_quality(TOKENS, 300.0, 400.0, config)
+
+    assert quality["columns"] == 0
+    assert quality["header_found"] is False
+    assert quality["assigned_fraction"] == pytest.approx(0.0)
+    assert quality["max_gutter_width"] == 0.0
+    assert hushdesk_accel.page_quality([], 300.0, 400.0)["body_tokens"] == 0
+
+
+def test_extract_page_indexes_rows_after_min_gap_merge() -> None:
+    # the readings sit 4px below their labels, closer than the minimum band width
+    tokens = [token for token in TOKENS if token[1] == 50.0] + [
+        (98.0, 100.0, "120/"),
+        (102.0, 104.0, "80"),
+        (150.0, 100.0, "135/"),
+        (152.0, 104.0, "85"),
+    ]
+
+    page = hushdesk_accel.extract_page(tokens, 300.0, 400.0)
+
+    assert page["rows"] == pytest.approx([50.0, 102.0])
+    assert page["vitals"] == {(1, 1): "120/80", (1, 2): "135/85"}
+    assert hushdesk_accel.page_quality(tokens, 300.0, 400.0)["rows"] == 2
+
+
+@pytest.mark.parametrize(
+    ("page_w", "page_h", "name"),
+    [(0.0, 400.0, "page_w"), (300.0, -1.0, "page_h"), (float("nan"), 400.0, "page_w")],
+)
+def test_extract_page_rejects_degenerate_page_sizes(page_w: float, page_h: float, name: str) -> None:
+    with pytest.raises(hushdesk_accel.HushdeskValueError, match=name):
+        hushdesk_accel.extract_page(TOKENS, page_w, page_h)
+    with pytest.raises(hushdesk_accel.HushdeskValueError, match=name):
+        hushdesk_accel.page_quality(TOKENS, page_w, page_h)
@@ -0,0 +1,112 @@
+"""Behavior checks for the Rust projection-profile helpers."""
+
+from __future__ import annotations
+
+import pytest
+
+hushdesk_accel = pytest.importorskip("hushdesk_accel")
+
+
+def test_column_gaps_reports_gutters_and_margins() -> None:
+    xs = [12.0, 14.0, 18.0, 52.0, 55.0]
+
+    gaps = hushdesk_accel.column_gaps(xs, 80.0, 4, 2)
+
+    assert gaps == pytest.approx([(0.0, 12.0), (20.0, 52.0), (56.0, 80.0)])
+
+
+def test_column_gaps_honors_min_gap_bins() -> None:
+    xs = [2.0, 10.0, 30.0]
+
+    gaps = hushdesk_accel.column_gaps(xs, 32.0, 4, 3)
+
+    assert gaps == pytest.approx([(12.0, 28.0)])
+
+
+def test_find_peaks_applies_height_and_plateau_midpoint() -> None:
+    profile = [0, 3, 0, 1, 0, 5, 5, 5, 0, 2]
+
+    peaks = hushdesk_accel.find_peaks(profile, 2, 1)
+
+    assert peaks == [1, 6, 9]
+
+
+def test_find_peaks_keeps_tallest_within_min_distance() -> None:
+    profile = [0, 4, 0, 6, 0, 0, 0, 3, 0]
+
+    peaks = hushdesk_accel.find_peaks(profile, 1, 3)
+
+    assert peaks == [3, 7]
+
+
+def test_find_valleys_reports_floor_midpoints_below_threshold() -> None:
+    profile = [0, 4, 0, 0, 0, 5, 2, 6, 3, 0]
+
+    assert hushdesk_accel.find_valleys(profile, 0) == [3]
+    assert hushdesk_accel.find_valleys(profile, 2) == [3, 6]
+
+
+def test_rle_profile_uses_inclusive_runs() -> None:
+    profile = [0, 0, 3, 1, 0, 2]
+
+    runs = hushdesk_accel.rle_profile(profile)
+
+    assert runs == [(False, 0, 1), (True, 2, 3), (False, 4, 4), (True, 5, 5)]
+    assert hushdesk_accel.rle_profile([]) == []
+
+
+def test_row_profile_counts_ys_per_bin() -> None:
+    ys = [1.0, 3.5, 9.0, 10.0, -1.0, 12.5, float("nan")]
+
+    profile = hushdesk_accel.row_profile(ys, 10.0, 4)
+
+    assert profile == [2, 0, 2]
+    assert hushdesk_accel.find_peaks(profile, 1, 1) == [0, 2]
+
+
+def test_occupied_runs_maps_dense_spans_to_coordinates() -> None:
+    profile = [0, 2, 3, 1, 0, 4, 4]
+
+    assert hushdesk_accel.occupied_runs(profile, 4, 100.0, 1) == pytest.approx(
+        [(104.0, 116.0), (120.0, 128.0)]
+    )
+    assert hushdesk_accel.occupied_runs(profile, 4, 100.0, 2) == pytest.approx(
+        [(104.0, 112.0), (120.0, 128.0)]
+    )
+    assert hushdesk_accel.occupied_runs(profile, 4, 0.0, 5) == []
+
+
+def test_segment_page_vertically_splits_on_clear_gaps() -> None:
+    header = [10.0, 14.0]
+    body = [100.0 + 12.0 * row for row in range(10) for _ in range(3)]
+    footer = [760.0]
+
+    top, middle, bottom = hushdesk_accel.segment_page_vertically(header + body + footer, 792.0, 4)
+
+    assert top == pytest.approx((8.0, 16.0))
+    assert middle == pytest.approx((100.0, 212.0))
+    assert bottom == pytest.approx((760.0, 764.0))
+
+
+def test_segment_page_vertically_splits_around_a_dense_body() -> None:
+    header = [10.0, 14.0]
+    body = [100.0 + 4.0 * row for row in range(50)]
+    footer = [760.0]
+
+    top, middle, bottom = hushdesk_accel.segment_page_vertically(header + body + footer, 792.0, 4)
+
+    assert top == pytest.approx((8.0, 16.0))
+    assert middle == pytest.approx((100.0, 300.0))
+    assert bottom == pytest.approx((760.0, 764.0))
+
+
+def test_segment_page_vertically_keeps_widely_spaced_rows_together() -> None:
+    # 30px gaps between rows exceed BLOCK_GAP_PX but match the row spacing
+    body = [100.0 + 40.0 * row for row in range(8)]
+
+    assert hushdesk_accel.segment_page_vertically(body, 792.0, 4) == (None, (100.0, 384.0), None)
+
+
+def test_segment_page_vertically_without_tokens_is_all_body() -> None:
+    assert hushdesk_accel.segment_page_vertically([], 792.0, 4) == (None, (0.0, 792.0), None)
+    assert hushdesk_accel.segment_page_vertically([100.0, 112.0], 792.0, 4)[::2] == (None, None)
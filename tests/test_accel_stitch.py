@@ -0,0 +1,179 @@
+"""Behavior checks for the Rust BP stitching helpers."""
+
+from __future__ import annotations
+
+import pytest
+
+hushdesk_accel = pytest.importorskip("hushdesk_accel")
+
+
+def test_stitch_bp_batch_preserves_page_order() -> None:
+    pages = [
+        ["120 /", "80"],
+        ["no reading"],
+        ["noise", "135/", "x", "85"],
+    ]
+
+    expected = [hushdesk_accel.stitch_bp(lines) for lines in pages]
+
+    assert hushdesk_accel.stitch_bp_batch(pages) == expected
+    assert hushdesk_accel.stitch_bp_batch(pages, parallel=False) == expected
+    assert expected == ["120/80", None, "135/85"]
+
+
+def test_merge_fragments_joins_close_tokens_in_x_order() -> None:
+    tokens = [(40.0, "mmHg"), (10.0, "BP"), (18.0, "120/80"), (90.0, "HR"), (97.0, "72")]
+
+    merged = hushdesk_accel.merge_fragments(tokens, 10.0)
+
+    assert merged == ["BP 120/80", "mmHg", "HR 72"]
+
+
+def test_stitch_bp_ascii_whitespace_keeps_nbsp() -> None:
+    lines = ["1\u00a020/", "80"]
+
+    assert hushdesk_accel.stitch_bp(lines) == "120/80"
+    assert hushdesk_accel.stitch_bp(lines, whitespace="ascii") is None
+    assert hushdesk_accel.stitch_bp(["120 /", " 80"], whitespace="ascii") == "120/80"
+
+
+def test_stitch_bp_explained_reports_failure_reasons() -> None:
+    explain = hushdesk_accel.stitch_bp_explained
+
+    assert explain(["120/", "80"]) == ("120/80", "")
+    assert explain(["120", "80"]) == (None, "no_slash_fragment")
+    assert explain(["120/", "HR"]) == (None, "no_numeric_suffix")
+    assert explain(["1200/", "80"]) == (None, "range_rejected")
+    assert explain(["120/", "8"]) == (None, "range_rejected")
+    assert explain(["abc/", "80"]) == (None, "non_numeric_prefix")
+    assert explain(["abc/", "120/", "HR"]) == (None, "no_numeric_suffix")
+
+
+def test_stitch_bp_ocr_repairs_confusable_digits() -> None:
+    assert hushdesk_accel.stitch_bp(["l20/", "8O"]) is None
+    assert hushdesk_accel.stitch_bp(["l20/", "8O"], ocr=True) == "120/80"
+    # fragments made only of look-alikes are repaired too
+    assert hushdesk_accel.stitch_bp(["120/", "SO", "85"], ocr=True) == "120/50"
+    assert hushdesk_accel.stitch_bp(["lOO/", "6O"], ocr=True) == "100/60"
+    # anything else alphabetic is left alone
+    assert hushdesk_accel.stitch_bp(["120/", "BP", "85"], ocr=True) == "120/85"
+    assert hushdesk_accel.stitch_bp(["12Z/", "80"], ocr=True, confusions={"Z": "2"}) == "122/80"
+
+
+def test_stitch_bp_reject_sentinels_keeps_scanning() -> None:
+    assert hushdesk_accel.stitch_bp(["999/", "999"]) == "999/999"
+    assert hushdesk_accel.stitch_bp(["999/", "999"], reject_sentinels=True) is None
+    assert hushdesk_accel.stitch_bp(["111/", "111", "85"], reject_sentinels=True) == "111/85"
+    assert hushdesk_accel.stitch_bp(["999/", "80", "120/", "80"], reject_sentinels=True) == "120/80"
+
+
+def test_stitch_bp_parts_flags_leading_zero() -> None:
+    assert hushdesk_accel.stitch_bp_parts(["120/", "80"]) == (120, 80, False)
+    assert hushdesk_accel.stitch_bp_parts(["120/", "08"]) == (120, 8, True)
+    assert hushdesk_accel.stitch_bp_parts(["HR", "72"]) is None
+
+
+def test_stitch_bp_in_band_ignores_neighbor_columns() -> None:
+    tokens = [(105.0, "120/"), (160.0, "80"), (110.0, "x"), (115.0, "75")]
+
+    assert hushdesk_accel.stitch_bp_in_band(tokens, (100.0, 150.0)) == "120/75"
+    assert hushdesk_accel.stitch_bp_in_band(tokens, (150.0, 200.0)) is None
+
+
+def test_stitch_bp_all_cells_keeps_one_result_per_cell() -> None:
+    cells = [["120/", "80"], [], ["HR 72"], ["135 /", "85"]]
+
+    assert hushdesk_accel.stitch_bp_all_cells(cells) == ["120/80", None, None, "135/85"]
+
+
+def test_stitch_bp_with_time_pairs_first_valid_time() -> None:
+    with_time = hushdesk_accel.stitch_bp_with_time
+
+    assert with_time(["25:10", "8:05", "120/", "80"]) == ("120/80", "08:05")
+    assert with_time(["120/", "80"]) == ("120/80", None)
+    assert with_time(["12:30"]) is None
+
+
+def test_stitch_bp_ints_applies_ranges_to_parsed_values() -> None:
+    lines = ["300/", "80", "120/", "080"]
+
+    assert hushdesk_accel.stitch_bp_ints(lines) == (300, 80)
+    assert hushdesk_accel.stitch_bp_ints(
+        lines, systolic_range=(60, 250), diastolic_range=(30, 150)
+    ) == (120, 80)
+    with pytest.raises(hushdesk_accel.HushdeskValueError):
+        hushdesk_accel.stitch_bp_ints(lines, systolic_range=(60, 250))
+
+
+def test_stitch_bp_canonical_strips_leading_zeros_on_request() -> None:
+    lines = ["120 /", " 080"]
+
+    assert hushdesk_accel.stitch_bp(lines) == "120/080"
+    assert hushdesk_accel.stitch_bp(lines, canonical=True) == "120/80"
+    assert hushdesk_accel.stitch_bp(lines, canonical=True, keep_leading_zeros=True) == "120/080"
+
+
+def test_flag_transposed_bp_lists_suspect_indices() -> None:
+    pairs = [(120, 80), (80, 120), (90, 90), (135, 85)]
+
+    assert hushdesk_accel.flag_transposed_bp(pairs) == [1, 2]
+    assert hushdesk_accel.flag_transposed_bp([(120, 80)]) == []
+
+
+def test_stitch_bp_backward_search_honors_max_skip() -> None:
+    reflowed = ["80", "note", "120/", "HR"]
+
+    assert hushdesk_accel.stitch_bp(reflowed) is None
+    assert hushdesk_accel.stitch_bp(reflowed, backward=True) == "120/80"
+    assert hushdesk_accel.stitch_bp(reflowed, backward=True, max_skip=0) is None
+    assert hushdesk_accel.stitch_bp(reflowed, backward=True, max_skip=1) == "120/80"
+    # forward matches still win and are bounded the same way
+    assert hushdesk_accel.stitch_bp(["70", "120/", "x", "80"], backward=True) == "120/80"
+    assert hushdesk_accel.stitch_bp(["120/", "x", "80"], max_skip=0) is None
+
+
+def test_stitchers_nan_policy_for_token_xs() -> None:
+    tokens = [(float("nan"), "120/"), (10.0, "80")]
+
+    assert hushdesk_accel.merge_fragments(tokens, 5.0) == ["80"]
+    assert hushdesk_accel.merge_fragments(tokens, 5.0, nan_policy="zero") == ["120/", "80"]
+    assert hushdesk_accel.stitch_bp_in_band(tokens, (0.0, 50.0), nan_policy="zero") == "120/80"
+    with pytest.raises(hushdesk_accel.HushdeskValueError):
+        hushdesk_accel.stitch_bp_in_band(tokens, (0.0, 50.0), nan_policy="error")
+
+
+def test_stitch_bp_str_splits_like_the_list_form() -> None:
+    assert hushdesk_accel.stitch_bp_str("BP\r\n120/\r\n80\r\n\r\n") == "120/80"
+    assert hushdesk_accel.stitch_bp_str("Pulse 72\n120/\n\n80") == hushdesk_accel.stitch_bp(
+        ["Pulse 72", "120/", "", "80"]
+    )
+    assert hushdesk_accel.stitch_bp_str("") is None
+
+
+def test_stitch_bp_predicate_rejects_and_keeps_scanning() -> None:
+    lines = ["120/", "81", "118/", "76"]
+    seen = []
+
+    def even_diastolic(systolic: int, diastolic: int) -> bool:
+        seen.append((systolic, diastolic))
+        return diastolic % 2 == 0
+
+    # the rejected 81 does not end the scan; the next suffix completes 120/
+    assert hushdesk_accel.stitch_bp(lines, predicate=even_diastolic) == "120/76"
+    assert seen == [(120, 81), (120, 76)]
+    assert hushdesk_accel.stitch_bp(lines, predicate=lambda s, d: False) is None
+
+
+def test_stitch_bp_predicate_errors_propagate() -> None:
+    def broken(systolic: int, diastolic: int) -> bool:
+        raise RuntimeError("form rule failed")
+
+    with pytest.raises(RuntimeError):
+        hushdesk_accel.stitch_bp(["120/", "80"], predicate=broken)
+
+
+def test_stitch_bp_in_band_swaps_reversed_band() -> None:
+    tokens = [(110.0, "120/"), (120.0, "80"), (300.0, "99")]
+
+    with pytest.warns(hushdesk_accel.HushdeskBandWarning, match="stitch_bp_in_band swapped 1"):
+        assert hushdesk_accel.stitch_bp_in_band(tokens, (150.0, 100.0)) == "120/80"
@@ -74,6 +74,27 @@ class ColumnSelectionTests(unittest.TestCase):
         self.assertAlmostEqual(bands[1].frac0, 25.0 / 80.0)
         self.assertAlmostEqual(bands[1].frac1, 45.0 / 80.0)
 
+    def test_select_audit_columns_skips_zero_width_pages(self) -> None:
+        pages = [
+            DummyPage(index=0, width=0.0, height=90.0),
+            DummyPage(index=1, width=60.0, height=90.0),
+        ]
+        doc = DummyDocument(pages)
+        missing: List[int] = []
+
+        def fake_centers(page: DummyPage) -> List[tuple[int, float]]:
+            return [(1, 10.0), (2, 30.0), (3, 50.0)]
+
+        with patch("hushdesk.pdf.columns.find_day_header_centers", side_effect=fake_centers):
+            bands = select_audit_columns(
+                doc, date(2025, 11, 2), on_page_without_header=missing.append
+            )
+
+        self.assertEqual(missing, [0])
+        self.assertEqual([band.page_index for band in bands], [1])
+        self.assertAlmostEqual(bands[0].x0, 20.0)
+        self.assertAlmostEqual(bands[0].x1, 40.0)
+
 
 if __name__ == "__main__":
     unittest.main()